@@ -1,6 +1,18 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Symbol, Vec};
 
-use crate::instance::{RandomnessSource, RaffleStatus};
+use crate::instance::{OracleFallback, RaffleStatus, RandomnessSource};
+
+// Helper function to publish events with standardized topics
+#[allow(deprecated)]
+pub(crate) fn publish_event<T>(env: &Env, event_name: &str, event: T)
+where
+    T: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
+{
+    env.events().publish(
+        (Symbol::new(env, "tikka"), Symbol::new(env, event_name)),
+        event,
+    );
+}
 
 // ============================================================================
 // LIFECYCLE EVENTS
@@ -11,10 +23,12 @@ use crate::instance::{RandomnessSource, RaffleStatus};
 #[contracttype]
 pub struct RaffleCreated {
     pub creator: Address,
+    pub start_time: u64,
     pub end_time: u64,
     pub max_tickets: u32,
     pub ticket_price: i128,
     pub payment_token: Address,
+    pub prize_token: Address,
     pub prize_amount: i128,
     pub description: String,
     pub randomness_source: RandomnessSource,
@@ -27,6 +41,26 @@ pub struct PrizeDeposited {
     pub creator: Address,
     pub amount: i128,
     pub token: Address,
+    pub nft_token_id: Option<u32>,
+    pub timestamp: u64,
+}
+
+/// Emitted when a third party contributes toward the prize
+#[derive(Clone)]
+#[contracttype]
+pub struct PrizeSponsored {
+    pub sponsor: Address,
+    pub amount: i128,
+    pub total_sponsored: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a sponsor's contribution is returned on cancellation
+#[derive(Clone)]
+#[contracttype]
+pub struct SponsorRefunded {
+    pub sponsor: Address,
+    pub amount: i128,
     pub timestamp: u64,
 }
 
@@ -35,6 +69,7 @@ pub struct PrizeDeposited {
 #[contracttype]
 pub struct TicketPurchased {
     pub buyer: Address,
+    pub payer: Address,
     pub ticket_ids: Vec<u32>,
     pub quantity: u32,
     pub total_paid: i128,
@@ -55,6 +90,19 @@ pub struct DrawTriggered {
 #[contracttype]
 pub struct RandomnessRequested {
     pub oracle: Address,
+    pub request_id: BytesN<32>,
+    pub deadline: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when the oracle deadline passes and the fallback path is taken
+#[derive(Clone)]
+#[contracttype]
+pub struct OracleTimedOut {
+    pub oracle: Address,
+    pub request_id: BytesN<32>,
+    pub deadline: u64,
+    pub fallback: OracleFallback,
     pub timestamp: u64,
 }
 
@@ -63,6 +111,16 @@ pub struct RandomnessRequested {
 #[contracttype]
 pub struct RandomnessReceived {
     pub oracle: Address,
+    pub request_id: BytesN<32>,
+    pub seed: u64,
+    pub timestamp: u64,
+}
+
+/// Emitted when the creator reveals the committed secret
+#[derive(Clone)]
+#[contracttype]
+pub struct SecretRevealed {
+    pub creator: Address,
     pub seed: u64,
     pub timestamp: u64,
 }
@@ -78,6 +136,17 @@ pub struct RaffleFinalized {
     pub finalized_at: u64,
 }
 
+/// Emitted once per prize tier when its winning ticket is drawn
+#[derive(Clone)]
+#[contracttype]
+pub struct WinnerDrawn {
+    pub tier: u32,
+    pub winner: Address,
+    pub ticket_id: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
 /// Emitted when a raffle is cancelled by the creator
 #[derive(Clone)]
 #[contracttype]
@@ -88,6 +157,17 @@ pub struct RaffleCancelled {
     pub timestamp: u64,
 }
 
+/// Emitted when sales close without reaching the minimum ticket threshold
+#[derive(Clone)]
+#[contracttype]
+pub struct RaffleFailed {
+    pub creator: Address,
+    pub reason: String,
+    pub tickets_sold: u32,
+    pub min_tickets: u32,
+    pub timestamp: u64,
+}
+
 /// Emitted when a ticket holder receives a refund
 #[derive(Clone)]
 #[contracttype]
@@ -103,12 +183,27 @@ pub struct TicketRefunded {
 #[contracttype]
 pub struct PrizeClaimed {
     pub winner: Address,
+    pub tier: u32,
+    pub token: Address,
+    pub nft_token_id: Option<u32>,
     pub gross_amount: i128,
     pub net_amount: i128,
     pub platform_fee: i128,
     pub claimed_at: u64,
 }
 
+/// Emitted when the creator withdraws ticket sale proceeds
+#[derive(Clone)]
+#[contracttype]
+pub struct ProceedsWithdrawn {
+    pub creator: Address,
+    pub gross_amount: i128,
+    pub net_amount: i128,
+    pub platform_fee: i128,
+    pub token: Address,
+    pub timestamp: u64,
+}
+
 // ============================================================================
 // ADMIN EVENTS
 // ============================================================================
@@ -1,17 +1,37 @@
 // Instance submodule
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env,
-    String, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, token, xdr::ToXdr,
+    Address, Bytes, BytesN, Env, String, Vec,
 };
 
 use crate::events::{
-    DrawTriggered, PrizeClaimed, PrizeDeposited, RaffleCancelled, RaffleCreated,
-    RaffleFinalized, RandomnessReceived, RandomnessRequested, StatusChanged, TicketPurchased,
+    publish_event, ContractPaused, ContractUnpaused, DrawTriggered, OracleTimedOut, PrizeClaimed,
+    PrizeDeposited, PrizeSponsored, ProceedsWithdrawn, RaffleCancelled, RaffleCreated,
+    RaffleFailed, RaffleFinalized, RandomnessReceived, RandomnessRequested, SecretRevealed,
+    SponsorRefunded, StatusChanged, TicketPurchased, TicketRefunded, WinnerDrawn,
 };
+use crate::nft::NftClient;
 
 #[contract]
 pub struct Contract;
 
+/// Factory entry points an instance calls back into.
+#[allow(dead_code)]
+#[contractclient(name = "FactoryClient")]
+pub trait Factory {
+    fn is_paused(env: Env) -> bool;
+}
+
+/// Upper bound for any fee expressed in basis points (100%).
+pub const MAX_FEE_BP: u32 = 10_000;
+
+/// Oracle response window used when a raffle does not configure one (24 hours).
+pub const DEFAULT_ORACLE_TIMEOUT: u64 = 24 * 60 * 60;
+
+/// Time after the oracle deadline when anyone may force a refund, even if the
+/// configured fallback is a creator-run `Prng` draw (24 hours).
+pub const ORACLE_REFUND_GRACE: u64 = 24 * 60 * 60;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[contracttype]
 pub enum RaffleStatus {
@@ -21,6 +41,7 @@ pub enum RaffleStatus {
     Finalized = 3,
     Claimed = 4,
     Cancelled = 5,
+    Failed = 6,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -28,6 +49,32 @@ pub enum RaffleStatus {
 pub enum RandomnessSource {
     Internal = 0,
     External = 1,
+    CommitReveal = 2,
+    Prng = 3,
+}
+
+/// What happens to an `External` raffle whose oracle misses its deadline.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[contracttype]
+pub enum OracleFallback {
+    Prng = 0,
+    Refund = 1,
+}
+
+/// What the raffle pays out: a fungible token amount or a single NFT.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[contracttype]
+pub enum PrizeKind {
+    Token = 0,
+    Nft = 1,
+}
+
+/// An extra fungible prize paid to the winner alongside the main prize.
+#[derive(Clone)]
+#[contracttype]
+pub struct PrizeComponent {
+    pub token: Address,
+    pub amount: i128,
 }
 
 #[derive(Clone)]
@@ -35,18 +82,39 @@ pub enum RandomnessSource {
 pub struct Raffle {
     pub creator: Address,
     pub description: String,
+    pub start_time: u64,
     pub end_time: u64,
     pub max_tickets: u32,
     pub allow_multiple: bool,
+    pub max_tickets_per_address: u32,
     pub ticket_price: i128,
     pub payment_token: Address,
+    pub prize_token: Address,
+    pub prize_kind: PrizeKind,
+    pub prize_nft_id: u32,
     pub prize_amount: i128,
+    pub prize_bundle: Vec<PrizeComponent>,
+    pub prize_tiers: Vec<u32>,
+    pub pot_bp: u32,
+    pub prize_sponsored: i128,
+    pub min_tickets: u32,
+    pub allowlist_root: Option<BytesN<32>>,
+    pub gate_token: Option<Address>,
+    pub min_balance: i128,
     pub tickets_sold: u32,
     pub status: RaffleStatus,
     pub prize_deposited: bool,
+    pub proceeds_withdrawn: bool,
+    pub sales_paused: bool,
     pub winner: Option<Address>,
     pub randomness_source: RandomnessSource,
     pub oracle_address: Option<Address>,
+    pub oracle_public_key: Option<BytesN<32>>,
+    pub oracle_timeout: u64,
+    pub oracle_fallback: OracleFallback,
+    pub commitment: Option<BytesN<32>>,
+    pub reveal_window: u64,
+    pub reveal_bond: i128,
     pub protocol_fee_bp: u32,
     pub treasury_address: Option<Address>,
 }
@@ -55,18 +123,47 @@ pub struct Raffle {
 #[contracttype]
 pub struct RaffleConfig {
     pub description: String,
+    pub start_time: u64,
     pub end_time: u64,
     pub max_tickets: u32,
     pub allow_multiple: bool,
+    pub max_tickets_per_address: u32,
     pub ticket_price: i128,
     pub payment_token: Address,
+    pub prize_token: Address,
+    pub prize_kind: PrizeKind,
+    pub prize_nft_id: u32,
     pub prize_amount: i128,
+    pub prize_bundle: Vec<PrizeComponent>,
+    pub prize_tiers: Vec<u32>,
+    pub pot_bp: u32,
+    pub min_tickets: u32,
+    pub allowlist_root: Option<BytesN<32>>,
+    pub gate_token: Option<Address>,
+    pub min_balance: i128,
     pub randomness_source: RandomnessSource,
     pub oracle_address: Option<Address>,
+    pub oracle_public_key: Option<BytesN<32>>,
+    pub oracle_timeout: u64,
+    pub oracle_fallback: OracleFallback,
+    pub commitment: Option<BytesN<32>>,
+    pub reveal_window: u64,
+    pub reveal_bond: i128,
     pub protocol_fee_bp: u32,
     pub treasury_address: Option<Address>,
 }
 
+/// One drawn prize tier. Tier 0 is the top prize.
+#[derive(Clone)]
+#[contracttype]
+pub struct PrizeWinner {
+    pub tier: u32,
+    pub winner: Address,
+    pub ticket_id: u32,
+    pub amount: i128,
+    pub claimed: bool,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct Ticket {
@@ -76,17 +173,6 @@ pub struct Ticket {
     pub ticket_number: u32,
 }
 
-// Helper function to publish events with standardized topics
-fn publish_event<T>(env: &Env, event_name: &str, event: T)
-where
-    T: soroban_sdk::IntoVal<Env, soroban_sdk::Val>,
-{
-    env.events().publish(
-        (Symbol::new(env, "tikka"), Symbol::new(env, event_name)),
-        event,
-    );
-}
-
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -96,6 +182,15 @@ pub enum DataKey {
     Ticket(u32),
     NextTicketId,
     Factory,
+    SalesPausedAt,
+    RevealBond,
+    RevealDeadline,
+    DrawSequence,
+    DrawEntropy,
+    RandomnessRequest,
+    OracleDeadline,
+    Winners,
+    Sponsorship(Address),
 }
 
 // --- Error Types ---
@@ -123,6 +218,39 @@ pub enum Error {
     AlreadyInitialized = 18,
     NotInitialized = 19,
     InvalidStateTransition = 20,
+    ProceedsAlreadyWithdrawn = 21,
+    NoRefundAvailable = 22,
+    InvalidReveal = 23,
+    RevealWindowClosed = 24,
+    InvalidRandomnessRequest = 25,
+    TicketLimitExceeded = 26,
+    RaffleNotStarted = 27,
+    InvalidProof = 28,
+    NotEligible = 29,
+}
+
+fn read_winners(env: &Env) -> Vec<PrizeWinner> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Winners)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn write_winners(env: &Env, winners: &Vec<PrizeWinner>) {
+    env.storage().instance().set(&DataKey::Winners, winners);
+}
+
+fn read_sponsorship(env: &Env, sponsor: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Sponsorship(sponsor.clone()))
+        .unwrap_or(0)
+}
+
+fn write_sponsorship(env: &Env, sponsor: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Sponsorship(sponsor.clone()), &amount);
 }
 
 fn read_raffle(env: &Env) -> Result<Raffle, Error> {
@@ -177,6 +305,547 @@ fn write_ticket(env: &Env, ticket: &Ticket) {
         .set(&DataKey::Ticket(ticket.id), ticket);
 }
 
+// Instances defer to the factory's global pause switch for state-advancing calls.
+fn ensure_not_paused(env: &Env) -> Result<(), Error> {
+    let factory: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Factory)
+        .ok_or(Error::NotInitialized)?;
+    if FactoryClient::new(env, &factory).is_paused() {
+        return Err(Error::ContractPaused);
+    }
+    Ok(())
+}
+
+fn release_reveal_bond(env: &Env, raffle: &Raffle, recipient: &Address) {
+    let bond: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RevealBond)
+        .unwrap_or(0);
+    if bond > 0 {
+        let token_client = token::Client::new(env, &raffle.payment_token);
+        token_client.transfer(&env.current_contract_address(), recipient, &bond);
+    }
+    env.storage().instance().remove(&DataKey::RevealBond);
+}
+
+// Moves `amount` of the prize; an NFT prize always moves its single escrowed token.
+fn transfer_prize(env: &Env, raffle: &Raffle, from: &Address, to: &Address, amount: i128) {
+    match raffle.prize_kind {
+        PrizeKind::Token => {
+            token::Client::new(env, &raffle.prize_token).transfer(from, to, &amount);
+        }
+        PrizeKind::Nft => {
+            NftClient::new(env, &raffle.prize_token).transfer(from, to, &raffle.prize_nft_id);
+        }
+    }
+}
+
+fn nft_token_id(raffle: &Raffle) -> Option<u32> {
+    match raffle.prize_kind {
+        PrizeKind::Token => None,
+        PrizeKind::Nft => Some(raffle.prize_nft_id),
+    }
+}
+
+// The creator gets back the part they deposited. Sponsors pull their own share
+// with `claim_sponsor_refund`, so cancelling never walks an open-ended sponsor list.
+fn return_prize(env: &Env, raffle: &mut Raffle) {
+    let contract_address = env.current_contract_address();
+
+    if raffle.prize_deposited {
+        let creator_share = raffle.prize_amount - raffle.prize_sponsored;
+        if creator_share > 0 {
+            transfer_prize(
+                env,
+                raffle,
+                &contract_address,
+                &raffle.creator,
+                creator_share,
+            );
+        }
+        for component in raffle.prize_bundle.iter() {
+            token::Client::new(env, &component.token).transfer(
+                &contract_address,
+                &raffle.creator,
+                &component.amount,
+            );
+        }
+        raffle.prize_deposited = false;
+    }
+}
+
+fn refund_sponsor(env: &Env, raffle: &mut Raffle, sponsor: &Address) -> Result<i128, Error> {
+    let amount = read_sponsorship(env, sponsor);
+    if amount == 0 {
+        return Err(Error::NoRefundAvailable);
+    }
+
+    write_sponsorship(env, sponsor, 0);
+    raffle.prize_sponsored -= amount;
+    write_raffle(env, raffle);
+
+    let contract_address = env.current_contract_address();
+    transfer_prize(env, raffle, &contract_address, sponsor, amount);
+    publish_event(
+        env,
+        "sponsor_refunded",
+        SponsorRefunded {
+            sponsor: sponsor.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    Ok(amount)
+}
+
+fn cancel_with_reason(env: &Env, raffle: &mut Raffle, reason: &str) {
+    let old_status = raffle.status.clone();
+    raffle.status = RaffleStatus::Cancelled;
+    return_prize(env, raffle);
+    write_raffle(env, raffle);
+
+    publish_event(
+        env,
+        "raffle_cancelled",
+        RaffleCancelled {
+            creator: raffle.creator.clone(),
+            reason: String::from_str(env, reason),
+            tickets_sold: raffle.tickets_sold,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    publish_event(
+        env,
+        "status_changed",
+        StatusChanged {
+            old_status,
+            new_status: RaffleStatus::Cancelled,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
+
+// Sales closed short of `min_tickets`: unwind everything and open buyer refunds.
+fn fail_raffle(env: &Env, raffle: &mut Raffle) {
+    release_reveal_bond(env, raffle, &raffle.creator);
+    raffle.status = RaffleStatus::Failed;
+    return_prize(env, raffle);
+    write_raffle(env, raffle);
+
+    publish_event(
+        env,
+        "raffle_failed",
+        RaffleFailed {
+            creator: raffle.creator.clone(),
+            reason: String::from_str(env, "Minimum tickets not reached"),
+            tickets_sold: raffle.tickets_sold,
+            min_tickets: raffle.min_tickets,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+
+    publish_event(
+        env,
+        "status_changed",
+        StatusChanged {
+            old_status: RaffleStatus::Active,
+            new_status: RaffleStatus::Failed,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
+
+fn refunds_open(raffle: &Raffle) -> bool {
+    raffle.status == RaffleStatus::Cancelled || raffle.status == RaffleStatus::Failed
+}
+
+fn seed_from_digest(digest: &BytesN<32>) -> u64 {
+    let bytes = digest.to_array();
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&bytes[..8]);
+    u64::from_be_bytes(seed)
+}
+
+/// Randomness backing a single draw.
+enum Entropy {
+    /// A seed supplied by the ledger, an oracle or a revealed secret.
+    Seed(u64),
+    /// The network-seeded PRNG, sampled without modulo bias.
+    Prng,
+}
+
+fn ticket_index(env: &Env, entropy: &Entropy, ticket_count: u32) -> u32 {
+    match entropy {
+        Entropy::Seed(seed) => (seed % ticket_count as u64) as u32,
+        Entropy::Prng => env.prng().gen_range::<u64>(0..ticket_count as u64) as u32,
+    }
+}
+
+// Each tier after the first draws from a seed chained off the previous one.
+fn next_entropy(env: &Env, entropy: Entropy) -> Entropy {
+    match entropy {
+        Entropy::Seed(seed) => {
+            let digest: BytesN<32> = env
+                .crypto()
+                .sha256(&Bytes::from_array(env, &seed.to_be_bytes()))
+                .into();
+            Entropy::Seed(seed_from_digest(&digest))
+        }
+        Entropy::Prng => Entropy::Prng,
+    }
+}
+
+fn ticket_revenue(raffle: &Raffle) -> Result<i128, Error> {
+    raffle
+        .ticket_price
+        .checked_mul(raffle.tickets_sold as i128)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// Share of ticket revenue that goes into the prize pot (zero for fixed-prize raffles).
+fn pot_share(raffle: &Raffle) -> Result<i128, Error> {
+    Ok(ticket_revenue(raffle)?
+        .checked_mul(raffle.pot_bp as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_FEE_BP as i128)
+}
+
+/// Total prize at draw time: the escrowed seed plus the pot share of sales.
+fn total_prize(raffle: &Raffle) -> Result<i128, Error> {
+    let seed = if raffle.prize_deposited {
+        raffle.prize_amount
+    } else {
+        0
+    };
+    seed.checked_add(pot_share(raffle)?)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// Splits the prize across tiers; the last tier absorbs any rounding dust.
+fn tier_amounts(env: &Env, raffle: &Raffle) -> Result<Vec<i128>, Error> {
+    let prize = total_prize(raffle)?;
+    let mut amounts = Vec::new(env);
+    if raffle.prize_tiers.is_empty() {
+        amounts.push_back(prize);
+        return Ok(amounts);
+    }
+
+    let mut allocated = 0i128;
+    let last = raffle.prize_tiers.len() - 1;
+    for (tier, share_bp) in raffle.prize_tiers.iter().enumerate() {
+        let amount = if tier as u32 == last {
+            prize - allocated
+        } else {
+            prize
+                .checked_mul(share_bp as i128)
+                .ok_or(Error::ArithmeticOverflow)?
+                / MAX_FEE_BP as i128
+        };
+        allocated += amount;
+        amounts.push_back(amount);
+    }
+    Ok(amounts)
+}
+
+fn complete_draw(
+    env: &Env,
+    raffle: &mut Raffle,
+    mut entropy: Entropy,
+    source: RandomnessSource,
+) -> Result<Address, Error> {
+    let tickets = read_tickets(env);
+    if tickets.is_empty() {
+        return Err(Error::NoTicketsSold);
+    }
+
+    let amounts = tier_amounts(env, raffle)?;
+    let timestamp = env.ledger().timestamp();
+    let mut winners = Vec::new(env);
+    // Ticket indices already drawn, kept sorted for the skip-ahead below.
+    let mut drawn: Vec<u32> = Vec::new(env);
+    let mut unawarded = 0i128;
+
+    for (tier, amount) in amounts.iter().enumerate() {
+        if drawn.len() == tickets.len() {
+            unawarded += amount;
+            continue;
+        }
+        if tier > 0 {
+            entropy = next_entropy(env, entropy);
+        }
+
+        // Pick among the remaining tickets, then map back to an absolute index.
+        let mut index = ticket_index(env, &entropy, tickets.len() - drawn.len());
+        let mut position = drawn.len();
+        for (i, taken) in drawn.iter().enumerate() {
+            if taken <= index {
+                index += 1;
+            } else {
+                position = i as u32;
+                break;
+            }
+        }
+        drawn.insert(position, index);
+
+        let winner = tickets.get(index).expect("Ticket out of bounds");
+        winners.push_back(PrizeWinner {
+            tier: tier as u32,
+            winner: winner.clone(),
+            ticket_id: index + 1,
+            amount,
+            claimed: false,
+        });
+
+        publish_event(
+            env,
+            "winner_drawn",
+            WinnerDrawn {
+                tier: tier as u32,
+                winner,
+                ticket_id: index + 1,
+                amount,
+                timestamp,
+            },
+        );
+    }
+
+    // Tiers left empty because fewer tickets sold than prizes go back to the creator.
+    if unawarded > 0 {
+        let contract_address = env.current_contract_address();
+        transfer_prize(env, raffle, &contract_address, &raffle.creator, unawarded);
+    }
+
+    let top = winners.get(0).expect("At least one tier is drawn");
+    write_winners(env, &winners);
+
+    raffle.status = RaffleStatus::Finalized;
+    raffle.winner = Some(top.winner.clone());
+    write_raffle(env, raffle);
+
+    publish_event(
+        env,
+        "raffle_finalized",
+        RaffleFinalized {
+            winner: top.winner.clone(),
+            winning_ticket_id: top.ticket_id,
+            total_tickets_sold: raffle.tickets_sold,
+            randomness_source: source,
+            finalized_at: timestamp,
+        },
+    );
+
+    publish_event(
+        env,
+        "status_changed",
+        StatusChanged {
+            old_status: RaffleStatus::Drawing,
+            new_status: RaffleStatus::Finalized,
+            timestamp,
+        },
+    );
+
+    Ok(top.winner)
+}
+
+// `tickets` is the ticket ledger, read once by the caller so batch refunds
+// don't reload it for every buyer.
+fn refund_buyer(
+    env: &Env,
+    raffle: &Raffle,
+    tickets: &Vec<Address>,
+    buyer: &Address,
+) -> Result<i128, Error> {
+    let count = read_ticket_count(env, buyer);
+    if count == 0 || raffle.ticket_price == 0 {
+        return Ok(0);
+    }
+
+    let amount = raffle
+        .ticket_price
+        .checked_mul(count as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    // Zero the count before paying out so a buyer can never be refunded twice.
+    write_ticket_count(env, buyer, 0);
+
+    let token_client = token::Client::new(env, &raffle.payment_token);
+    token_client.transfer(&env.current_contract_address(), buyer, &amount);
+
+    let timestamp = env.ledger().timestamp();
+    for (index, holder) in tickets.iter().enumerate() {
+        if holder == *buyer {
+            publish_event(
+                env,
+                "ticket_refunded",
+                TicketRefunded {
+                    buyer: buyer.clone(),
+                    ticket_id: index as u32 + 1,
+                    amount: raffle.ticket_price,
+                    timestamp,
+                },
+            );
+        }
+    }
+
+    Ok(amount)
+}
+
+// Leaves are sha256(address xdr || allocation xdr); pairs are hashed in sorted order.
+fn verify_allowlist(
+    env: &Env,
+    root: &BytesN<32>,
+    buyer: &Address,
+    allocation: u32,
+    proof: &Vec<BytesN<32>>,
+) -> bool {
+    let mut leaf_src = buyer.clone().to_xdr(env);
+    leaf_src.append(&allocation.to_xdr(env));
+    let mut node: BytesN<32> = env.crypto().sha256(&leaf_src).into();
+
+    for sibling in proof.iter() {
+        let (left, right) = if node < sibling {
+            (node, sibling)
+        } else {
+            (sibling, node)
+        };
+        let mut pair = Bytes::from(left);
+        pair.append(&Bytes::from(right));
+        node = env.crypto().sha256(&pair).into();
+    }
+
+    node == *root
+}
+
+// `payer` funds the purchase; `buyer` receives the tickets and is checked for eligibility.
+fn purchase_tickets(
+    env: &Env,
+    payer: &Address,
+    buyer: &Address,
+    quantity: u32,
+    allowlisted: bool,
+) -> Result<u32, Error> {
+    let mut raffle = read_raffle(env)?;
+    ensure_not_paused(env)?;
+
+    if raffle.allowlist_root.is_some() && !allowlisted {
+        return Err(Error::InvalidProof);
+    }
+    if let Some(gate_token) = raffle.gate_token.as_ref() {
+        if token::Client::new(env, gate_token).balance(buyer) < raffle.min_balance {
+            return Err(Error::NotEligible);
+        }
+    }
+
+    if raffle.status != RaffleStatus::Active {
+        return Err(Error::RaffleInactive);
+    }
+    if raffle.sales_paused {
+        return Err(Error::ContractPaused);
+    }
+    if env.ledger().timestamp() < raffle.start_time {
+        return Err(Error::RaffleNotStarted);
+    }
+    if raffle.end_time != 0 && env.ledger().timestamp() > raffle.end_time {
+        return Err(Error::RaffleEnded);
+    }
+    if quantity == 0 {
+        return Err(Error::InvalidParameters);
+    }
+    if raffle.tickets_sold >= raffle.max_tickets {
+        return Err(Error::TicketsSoldOut);
+    }
+    let new_total = raffle
+        .tickets_sold
+        .checked_add(quantity)
+        .ok_or(Error::ArithmeticOverflow)?;
+    if new_total > raffle.max_tickets {
+        return Err(Error::InsufficientTickets);
+    }
+
+    // Free entries cost nothing, so each address gets exactly one regardless of config.
+    let current_count = read_ticket_count(env, buyer);
+    let single_entry = !raffle.allow_multiple || raffle.ticket_price == 0;
+    if single_entry && (current_count > 0 || quantity > 1) {
+        return Err(Error::MultipleTicketsNotAllowed);
+    }
+    if raffle.max_tickets_per_address > 0 {
+        let buyer_total = current_count
+            .checked_add(quantity)
+            .ok_or(Error::ArithmeticOverflow)?;
+        if buyer_total > raffle.max_tickets_per_address {
+            return Err(Error::TicketLimitExceeded);
+        }
+    }
+
+    let total_paid = raffle
+        .ticket_price
+        .checked_mul(quantity as i128)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    if total_paid > 0 {
+        let token_client = token::Client::new(env, &raffle.payment_token);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(payer, &contract_address, &total_paid);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let mut tickets = read_tickets(env);
+    let mut ticket_ids = Vec::new(env);
+
+    for _ in 0..quantity {
+        let ticket_id = next_ticket_id(env);
+        raffle.tickets_sold += 1;
+
+        let ticket = Ticket {
+            id: ticket_id,
+            buyer: buyer.clone(),
+            purchase_time: timestamp,
+            ticket_number: raffle.tickets_sold,
+        };
+        write_ticket(env, &ticket);
+        tickets.push_back(buyer.clone());
+        ticket_ids.push_back(ticket_id);
+    }
+    write_tickets(env, &tickets);
+
+    if raffle.tickets_sold >= raffle.max_tickets {
+        raffle.status = RaffleStatus::Drawing;
+        publish_event(
+            env,
+            "status_changed",
+            StatusChanged {
+                old_status: RaffleStatus::Active,
+                new_status: RaffleStatus::Drawing,
+                timestamp,
+            },
+        );
+    }
+
+    write_ticket_count(env, buyer, current_count + quantity);
+    write_raffle(env, &raffle);
+
+    publish_event(
+        env,
+        "ticket_purchased",
+        TicketPurchased {
+            buyer: buyer.clone(),
+            payer: payer.clone(),
+            ticket_ids,
+            quantity,
+            total_paid,
+            timestamp,
+        },
+    );
+
+    Ok(raffle.tickets_sold)
+}
+
+#[cfg(any(not(target_family = "wasm"), feature = "instance"))]
 #[contractimpl]
 impl Contract {
     pub fn init(
@@ -193,36 +862,133 @@ impl Contract {
         if config.end_time < now && config.end_time != 0 {
             return Err(Error::InvalidParameters);
         }
-        if config.max_tickets == 0 {
+        if config.end_time != 0 && config.start_time >= config.end_time {
+            return Err(Error::InvalidParameters);
+        }
+        if config.max_tickets == 0 || config.min_tickets > config.max_tickets {
+            return Err(Error::InvalidParameters);
+        }
+        if config.gate_token.is_some() && config.min_balance <= 0 {
+            return Err(Error::InvalidParameters);
+        }
+        // allow_multiple = false already means a cap of one ticket per address.
+        if !config.allow_multiple && config.max_tickets_per_address > 1 {
+            return Err(Error::InvalidParameters);
+        }
+        // A zero price makes a free-entry raffle; there is no revenue to build a pot from.
+        if config.ticket_price < 0 || (config.ticket_price == 0 && config.pot_bp > 0) {
+            return Err(Error::InvalidParameters);
+        }
+        // Pot raffles fund the prize from sales, so the seed deposit may be zero.
+        if config.prize_amount < 0 || (config.prize_amount == 0 && config.pot_bp == 0) {
+            return Err(Error::InvalidParameters);
+        }
+        if config.pot_bp > MAX_FEE_BP {
             return Err(Error::InvalidParameters);
         }
-        if config.ticket_price <= 0 {
+        // An NFT is a single indivisible item: one winner, no pot, no fee split.
+        if config.prize_kind == PrizeKind::Nft
+            && (config.prize_amount != 1 || config.pot_bp > 0 || config.prize_tiers.len() > 1)
+        {
+            return Err(Error::InvalidParameters);
+        }
+        // Bundled components all go to a single winner.
+        if !config.prize_bundle.is_empty() && config.prize_tiers.len() > 1 {
+            return Err(Error::InvalidParameters);
+        }
+        for component in config.prize_bundle.iter() {
+            if component.amount <= 0 {
+                return Err(Error::InvalidParameters);
+            }
+        }
+        // A pot is paid out of ticket sales, so it can only be denominated in the payment token.
+        if config.pot_bp > 0 && config.prize_token != config.payment_token {
             return Err(Error::InvalidParameters);
         }
-        if config.prize_amount <= 0 {
+        if config.protocol_fee_bp > MAX_FEE_BP {
             return Err(Error::InvalidParameters);
         }
+        if !config.prize_tiers.is_empty() {
+            if config.prize_tiers.len() > config.max_tickets {
+                return Err(Error::InvalidParameters);
+            }
+            let mut total_bp = 0u32;
+            for share_bp in config.prize_tiers.iter() {
+                if share_bp == 0 {
+                    return Err(Error::InvalidParameters);
+                }
+                total_bp = total_bp.saturating_add(share_bp);
+            }
+            if total_bp != MAX_FEE_BP {
+                return Err(Error::InvalidParameters);
+            }
+        }
 
-        if config.randomness_source == RandomnessSource::External && config.oracle_address.is_none()
+        if config.randomness_source == RandomnessSource::External
+            && (config.oracle_address.is_none() || config.oracle_public_key.is_none())
+        {
+            return Err(Error::InvalidParameters);
+        }
+        if config.randomness_source == RandomnessSource::CommitReveal
+            && (config.commitment.is_none() || config.reveal_window == 0)
         {
             return Err(Error::InvalidParameters);
         }
+        if config.reveal_bond < 0 {
+            return Err(Error::InvalidParameters);
+        }
+        // A forfeited bond needs somewhere to go other than back to the creator.
+        if config.reveal_bond > 0 && config.treasury_address.is_none() {
+            return Err(Error::InvalidParameters);
+        }
+
+        // With nothing to escrow up front, a pot raffle can open for sales straight away.
+        let opens_immediately = config.pot_bp > 0
+            && config.prize_amount == 0
+            && config.prize_bundle.is_empty()
+            && config.reveal_bond == 0;
+        let status = if opens_immediately {
+            RaffleStatus::Active
+        } else {
+            RaffleStatus::Proposed
+        };
 
         let raffle = Raffle {
             creator: creator.clone(),
             description: config.description.clone(),
+            start_time: config.start_time,
             end_time: config.end_time,
             max_tickets: config.max_tickets,
             allow_multiple: config.allow_multiple,
+            max_tickets_per_address: config.max_tickets_per_address,
             ticket_price: config.ticket_price,
             payment_token: config.payment_token.clone(),
+            prize_token: config.prize_token.clone(),
+            prize_kind: config.prize_kind.clone(),
+            prize_nft_id: config.prize_nft_id,
             prize_amount: config.prize_amount,
+            prize_bundle: config.prize_bundle,
+            prize_tiers: config.prize_tiers,
+            pot_bp: config.pot_bp,
+            prize_sponsored: 0,
+            min_tickets: config.min_tickets,
+            allowlist_root: config.allowlist_root,
+            gate_token: config.gate_token,
+            min_balance: config.min_balance,
             tickets_sold: 0,
-            status: RaffleStatus::Proposed,
+            status,
             prize_deposited: false,
+            proceeds_withdrawn: false,
+            sales_paused: false,
             winner: None,
             randomness_source: config.randomness_source.clone(),
             oracle_address: config.oracle_address,
+            oracle_public_key: config.oracle_public_key,
+            oracle_timeout: config.oracle_timeout,
+            oracle_fallback: config.oracle_fallback,
+            commitment: config.commitment,
+            reveal_window: config.reveal_window,
+            reveal_bond: config.reveal_bond,
             protocol_fee_bp: config.protocol_fee_bp,
             treasury_address: config.treasury_address,
         };
@@ -234,22 +1000,37 @@ impl Contract {
             "raffle_created",
             RaffleCreated {
                 creator,
+                start_time: config.start_time,
                 end_time: config.end_time,
                 max_tickets: config.max_tickets,
                 ticket_price: config.ticket_price,
                 payment_token: config.payment_token,
+                prize_token: config.prize_token,
                 prize_amount: config.prize_amount,
                 description: config.description,
                 randomness_source: config.randomness_source,
             },
         );
 
+        if opens_immediately {
+            publish_event(
+                &env,
+                "status_changed",
+                StatusChanged {
+                    old_status: RaffleStatus::Proposed,
+                    new_status: RaffleStatus::Active,
+                    timestamp: now,
+                },
+            );
+        }
+
         Ok(())
     }
 
     pub fn deposit_prize(env: Env) -> Result<(), Error> {
         let mut raffle = read_raffle(&env)?;
         raffle.creator.require_auth();
+        ensure_not_paused(&env)?;
 
         if raffle.status != RaffleStatus::Proposed {
             return Err(Error::InvalidStateTransition);
@@ -258,9 +1039,34 @@ impl Contract {
             return Err(Error::PrizeAlreadyDeposited);
         }
 
-        let token_client = token::Client::new(&env, &raffle.payment_token);
+        // The creator covers whatever sponsors have not already funded.
         let contract_address = env.current_contract_address();
-        token_client.transfer(&raffle.creator, &contract_address, &raffle.prize_amount);
+        let creator_share = raffle.prize_amount - raffle.prize_sponsored;
+        if creator_share > 0 {
+            transfer_prize(
+                &env,
+                &raffle,
+                &raffle.creator,
+                &contract_address,
+                creator_share,
+            );
+        }
+        for component in raffle.prize_bundle.iter() {
+            token::Client::new(&env, &component.token).transfer(
+                &raffle.creator,
+                &contract_address,
+                &component.amount,
+            );
+        }
+
+        // The reveal bond is always posted in the payment token.
+        if raffle.randomness_source == RandomnessSource::CommitReveal && raffle.reveal_bond > 0 {
+            let token_client = token::Client::new(&env, &raffle.payment_token);
+            token_client.transfer(&raffle.creator, &contract_address, &raffle.reveal_bond);
+            env.storage()
+                .instance()
+                .set(&DataKey::RevealBond, &raffle.reveal_bond);
+        }
 
         raffle.prize_deposited = true;
         raffle.status = RaffleStatus::Active;
@@ -271,12 +1077,27 @@ impl Contract {
             "prize_deposited",
             PrizeDeposited {
                 creator: raffle.creator.clone(),
-                amount: raffle.prize_amount,
-                token: raffle.payment_token.clone(),
+                amount: creator_share,
+                token: raffle.prize_token.clone(),
+                nft_token_id: nft_token_id(&raffle),
                 timestamp: env.ledger().timestamp(),
             },
         );
 
+        for component in raffle.prize_bundle.iter() {
+            publish_event(
+                &env,
+                "prize_deposited",
+                PrizeDeposited {
+                    creator: raffle.creator.clone(),
+                    amount: component.amount,
+                    token: component.token,
+                    nft_token_id: None,
+                    timestamp: env.ledger().timestamp(),
+                },
+            );
+        }
+
         publish_event(
             &env,
             "status_changed",
@@ -290,171 +1111,346 @@ impl Contract {
         Ok(())
     }
 
-    pub fn buy_ticket(env: Env, buyer: Address) -> Result<u32, Error> {
-        buyer.require_auth();
+    pub fn sponsor_prize(env: Env, sponsor: Address, amount: i128) -> Result<i128, Error> {
+        sponsor.require_auth();
         let mut raffle = read_raffle(&env)?;
+        ensure_not_paused(&env)?;
 
-        if raffle.status != RaffleStatus::Active {
-            return Err(Error::RaffleInactive);
-        }
-        if raffle.end_time != 0 && env.ledger().timestamp() > raffle.end_time {
-            return Err(Error::RaffleEnded);
+        if raffle.status != RaffleStatus::Proposed {
+            return Err(Error::InvalidStateTransition);
         }
-        if raffle.tickets_sold >= raffle.max_tickets {
-            return Err(Error::TicketsSoldOut);
+        if raffle.prize_kind != PrizeKind::Token {
+            return Err(Error::InvalidParameters);
         }
-
-        let current_count = read_ticket_count(&env, &buyer);
-        if !raffle.allow_multiple && current_count > 0 {
-            return Err(Error::MultipleTicketsNotAllowed);
+        let outstanding = raffle.prize_amount - raffle.prize_sponsored;
+        if amount <= 0 || amount > outstanding {
+            return Err(Error::InvalidParameters);
         }
 
-        let token_client = token::Client::new(&env, &raffle.payment_token);
         let contract_address = env.current_contract_address();
-        token_client.transfer(&buyer, &contract_address, &raffle.ticket_price);
-
-        let ticket_id = next_ticket_id(&env);
-        let timestamp = env.ledger().timestamp();
+        transfer_prize(&env, &raffle, &sponsor, &contract_address, amount);
 
-        let ticket = Ticket {
-            id: ticket_id,
-            buyer: buyer.clone(),
-            purchase_time: timestamp,
-            ticket_number: raffle.tickets_sold + 1,
-        };
-        write_ticket(&env, &ticket);
+        let previous = read_sponsorship(&env, &sponsor);
+        write_sponsorship(&env, &sponsor, previous + amount);
+        raffle.prize_sponsored += amount;
 
-        let mut tickets = read_tickets(&env);
-        tickets.push_back(buyer.clone());
-        write_tickets(&env, &tickets);
-
-        raffle.tickets_sold += 1;
+        let timestamp = env.ledger().timestamp();
+        publish_event(
+            &env,
+            "prize_sponsored",
+            PrizeSponsored {
+                sponsor,
+                amount,
+                total_sponsored: raffle.prize_sponsored,
+                timestamp,
+            },
+        );
 
-        if raffle.tickets_sold >= raffle.max_tickets {
-            raffle.status = RaffleStatus::Drawing;
+        // A fully sponsored prize opens sales, unless the creator still owes a bundle or bond.
+        let creator_owes_more = !raffle.prize_bundle.is_empty()
+            || (raffle.randomness_source == RandomnessSource::CommitReveal
+                && raffle.reveal_bond > 0);
+        if raffle.prize_sponsored == raffle.prize_amount && !creator_owes_more {
+            raffle.prize_deposited = true;
+            raffle.status = RaffleStatus::Active;
             publish_event(
                 &env,
                 "status_changed",
                 StatusChanged {
-                    old_status: RaffleStatus::Active,
-                    new_status: RaffleStatus::Drawing,
-                    timestamp: env.ledger().timestamp(),
+                    old_status: RaffleStatus::Proposed,
+                    new_status: RaffleStatus::Active,
+                    timestamp,
                 },
             );
         }
-
-        write_ticket_count(&env, &buyer, current_count + 1);
         write_raffle(&env, &raffle);
 
-        let mut ticket_ids = Vec::new(&env);
-        ticket_ids.push_back(ticket_id);
+        Ok(raffle.prize_sponsored)
+    }
+
+    // Sponsors can pull out while the raffle is still Proposed, so a creator who
+    // never deposits cannot strand their funds.
+    pub fn withdraw_sponsorship(env: Env, sponsor: Address) -> Result<i128, Error> {
+        sponsor.require_auth();
+        let mut raffle = read_raffle(&env)?;
+
+        if raffle.status != RaffleStatus::Proposed {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        refund_sponsor(&env, &mut raffle, &sponsor)
+    }
+
+    pub fn buy_ticket(env: Env, buyer: Address) -> Result<u32, Error> {
+        buyer.require_auth();
+        purchase_tickets(&env, &buyer, &buyer, 1, false)
+    }
+
+    pub fn buy_tickets(env: Env, buyer: Address, quantity: u32) -> Result<u32, Error> {
+        buyer.require_auth();
+        purchase_tickets(&env, &buyer, &buyer, quantity, false)
+    }
+
+    pub fn buy_ticket_for(
+        env: Env,
+        payer: Address,
+        recipient: Address,
+        quantity: u32,
+    ) -> Result<u32, Error> {
+        payer.require_auth();
+        // Free entries cost the payer nothing, so the recipient must opt in;
+        // otherwise anyone could pad the draw with entries for made-up addresses.
+        if read_raffle(&env)?.ticket_price == 0 {
+            recipient.require_auth();
+        }
+        purchase_tickets(&env, &payer, &recipient, quantity, false)
+    }
+
+    pub fn buy_ticket_with_proof(
+        env: Env,
+        buyer: Address,
+        quantity: u32,
+        allocation: u32,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<u32, Error> {
+        buyer.require_auth();
+        let raffle = read_raffle(&env)?;
+        let root = raffle.allowlist_root.ok_or(Error::InvalidStateTransition)?;
+
+        if !verify_allowlist(&env, &root, &buyer, allocation, &proof) {
+            return Err(Error::InvalidProof);
+        }
+        // A non-zero allocation caps this address on top of the raffle-wide limits.
+        if allocation > 0 {
+            let buyer_total = read_ticket_count(&env, &buyer)
+                .checked_add(quantity)
+                .ok_or(Error::ArithmeticOverflow)?;
+            if buyer_total > allocation {
+                return Err(Error::TicketLimitExceeded);
+            }
+        }
+
+        purchase_tickets(&env, &buyer, &buyer, quantity, true)
+    }
+
+    pub fn pause_sales(env: Env) -> Result<(), Error> {
+        let mut raffle = read_raffle(&env)?;
+        raffle.creator.require_auth();
+
+        if raffle.status != RaffleStatus::Active || raffle.sales_paused {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        let timestamp = env.ledger().timestamp();
+        raffle.sales_paused = true;
+        write_raffle(&env, &raffle);
+        env.storage()
+            .instance()
+            .set(&DataKey::SalesPausedAt, &timestamp);
 
         publish_event(
             &env,
-            "ticket_purchased",
-            TicketPurchased {
-                buyer,
-                ticket_ids,
-                quantity: 1u32,
-                total_paid: raffle.ticket_price,
+            "contract_paused",
+            ContractPaused {
+                paused_by: raffle.creator.clone(),
                 timestamp,
             },
         );
 
-        Ok(raffle.tickets_sold)
+        Ok(())
     }
 
-    pub fn finalize_raffle(env: Env) -> Result<(), Error> {
+    pub fn resume_sales(env: Env, extend_end_time: bool) -> Result<(), Error> {
         let mut raffle = read_raffle(&env)?;
         raffle.creator.require_auth();
 
-        if raffle.status == RaffleStatus::Active {
-            if (raffle.end_time != 0 && env.ledger().timestamp() >= raffle.end_time)
-                || raffle.tickets_sold >= raffle.max_tickets
-            {
-                raffle.status = RaffleStatus::Drawing;
-                publish_event(
-                    &env,
-                    "status_changed",
-                    StatusChanged {
-                        old_status: RaffleStatus::Active,
-                        new_status: RaffleStatus::Drawing,
-                        timestamp: env.ledger().timestamp(),
-                    },
-                );
-            }
-        }
-
-        if raffle.status != RaffleStatus::Drawing {
+        if raffle.status != RaffleStatus::Active || !raffle.sales_paused {
             return Err(Error::InvalidStateTransition);
         }
 
-        if raffle.tickets_sold == 0 {
-            return Err(Error::NoTicketsSold);
+        let timestamp = env.ledger().timestamp();
+        let paused_at: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SalesPausedAt)
+            .unwrap_or(timestamp);
+
+        // Give buyers back the selling window they lost while sales were halted.
+        if extend_end_time && raffle.end_time != 0 {
+            raffle.end_time = raffle
+                .end_time
+                .checked_add(timestamp - paused_at)
+                .ok_or(Error::ArithmeticOverflow)?;
         }
 
+        raffle.sales_paused = false;
+        write_raffle(&env, &raffle);
+        env.storage().instance().remove(&DataKey::SalesPausedAt);
+
         publish_event(
             &env,
-            "draw_triggered",
-            DrawTriggered {
-                triggered_by: raffle.creator.clone(),
-                total_tickets_sold: raffle.tickets_sold,
-                timestamp: env.ledger().timestamp(),
+            "contract_unpaused",
+            ContractUnpaused {
+                unpaused_by: raffle.creator.clone(),
+                timestamp,
             },
         );
 
-        if raffle.randomness_source == RandomnessSource::External {
-            let oracle = raffle
-                .oracle_address
-                .as_ref()
-                .expect("Oracle missing")
-                .clone();
+        Ok(())
+    }
+
+    // External and CommitReveal draws only fix their inputs here, so anyone may
+    // start them once sales close and the creator cannot pick the moment.
+    // Internal and Prng draws pick the winner in this very call; opening them up
+    // would let a ticket holder retry every ledger until they win.
+    pub fn finalize_raffle(env: Env, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+        let mut raffle = read_raffle(&env)?;
+        ensure_not_paused(&env)?;
+        // The creator may still resume with an extended end_time, so a paused
+        // raffle can be neither drawn nor failed.
+        if raffle.sales_paused {
+            return Err(Error::ContractPaused);
+        }
+        if caller != raffle.creator
+            && matches!(
+                raffle.randomness_source,
+                RandomnessSource::Internal | RandomnessSource::Prng
+            )
+        {
+            return Err(Error::NotAuthorized);
+        }
+
+        if raffle.status == RaffleStatus::Active
+            && raffle.end_time != 0
+            && env.ledger().timestamp() >= raffle.end_time
+            && raffle.tickets_sold < raffle.min_tickets
+        {
+            fail_raffle(&env, &mut raffle);
+            return Ok(());
+        }
+
+        if raffle.status == RaffleStatus::Active
+            && ((raffle.end_time != 0 && env.ledger().timestamp() >= raffle.end_time)
+                || raffle.tickets_sold >= raffle.max_tickets)
+        {
+            raffle.status = RaffleStatus::Drawing;
             publish_event(
                 &env,
-                "randomness_requested",
-                RandomnessRequested {
-                    oracle,
+                "status_changed",
+                StatusChanged {
+                    old_status: RaffleStatus::Active,
+                    new_status: RaffleStatus::Drawing,
                     timestamp: env.ledger().timestamp(),
                 },
             );
-            return Ok(());
         }
 
-        let tickets = read_tickets(&env);
-        let seed = env.ledger().timestamp() + env.ledger().sequence() as u64;
-        let winner_index = (seed % tickets.len() as u64) as u32;
-        let winner = tickets.get(winner_index).expect("Ticket out of bounds");
+        if raffle.status != RaffleStatus::Drawing {
+            return Err(Error::InvalidStateTransition);
+        }
 
-        raffle.status = RaffleStatus::Finalized;
-        raffle.winner = Some(winner.clone());
-        write_raffle(&env, &raffle);
+        if raffle.tickets_sold == 0 {
+            return Err(Error::NoTicketsSold);
+        }
 
         publish_event(
             &env,
-            "raffle_finalized",
-            RaffleFinalized {
-                winner: winner.clone(),
-                winning_ticket_id: winner_index,
+            "draw_triggered",
+            DrawTriggered {
+                triggered_by: caller,
                 total_tickets_sold: raffle.tickets_sold,
-                randomness_source: RandomnessSource::Internal,
-                finalized_at: env.ledger().timestamp(),
-            },
-        );
-
-        publish_event(
-            &env,
-            "status_changed",
-            StatusChanged {
-                old_status: RaffleStatus::Drawing,
-                new_status: RaffleStatus::Finalized,
                 timestamp: env.ledger().timestamp(),
             },
         );
 
-        Ok(())
+        match raffle.randomness_source {
+            RandomnessSource::External => {
+                let oracle = raffle
+                    .oracle_address
+                    .as_ref()
+                    .expect("Oracle missing")
+                    .clone();
+                // Bind the request to this contract and ledger so responses cannot be replayed.
+                let mut request_src = env.current_contract_address().to_xdr(&env);
+                request_src.append(&env.ledger().sequence().to_xdr(&env));
+                request_src.append(&env.ledger().timestamp().to_xdr(&env));
+                let request_id: BytesN<32> = env.crypto().sha256(&request_src).into();
+                env.storage()
+                    .instance()
+                    .set(&DataKey::RandomnessRequest, &request_id);
+
+                let timeout = if raffle.oracle_timeout == 0 {
+                    DEFAULT_ORACLE_TIMEOUT
+                } else {
+                    raffle.oracle_timeout
+                };
+                let deadline = env
+                    .ledger()
+                    .timestamp()
+                    .checked_add(timeout)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::OracleDeadline, &deadline);
+
+                write_raffle(&env, &raffle);
+                publish_event(
+                    &env,
+                    "randomness_requested",
+                    RandomnessRequested {
+                        oracle,
+                        request_id,
+                        deadline,
+                        timestamp: env.ledger().timestamp(),
+                    },
+                );
+                Ok(())
+            }
+            RandomnessSource::CommitReveal => {
+                let deadline = env
+                    .ledger()
+                    .timestamp()
+                    .checked_add(raffle.reveal_window)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::RevealDeadline, &deadline);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::DrawSequence, &env.ledger().sequence());
+                // Sampled after the commitment is locked, so the creator cannot
+                // pick a secret that favours a known outcome.
+                let entropy: BytesN<32> = env.prng().gen();
+                env.storage()
+                    .instance()
+                    .set(&DataKey::DrawEntropy, &entropy);
+                write_raffle(&env, &raffle);
+                Ok(())
+            }
+            RandomnessSource::Internal => {
+                let seed = env.ledger().timestamp() + env.ledger().sequence() as u64;
+                complete_draw(
+                    &env,
+                    &mut raffle,
+                    Entropy::Seed(seed),
+                    RandomnessSource::Internal,
+                )?;
+                Ok(())
+            }
+            RandomnessSource::Prng => {
+                complete_draw(&env, &mut raffle, Entropy::Prng, RandomnessSource::Prng)?;
+                Ok(())
+            }
+        }
     }
 
-    pub fn provide_randomness(env: Env, random_seed: u64) -> Result<Address, Error> {
+    pub fn provide_randomness(
+        env: Env,
+        request_id: BytesN<32>,
+        output: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Result<Address, Error> {
         let mut raffle = read_raffle(&env)?;
         match &raffle.oracle_address {
             Some(oracle) => oracle.require_auth(),
@@ -467,52 +1463,207 @@ impl Contract {
             return Err(Error::InvalidStateTransition);
         }
 
-        let tickets = read_tickets(&env);
-        if tickets.len() == 0 {
-            return Err(Error::NoTicketsSold);
+        let pending: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::RandomnessRequest)
+            .ok_or(Error::InvalidRandomnessRequest)?;
+        if request_id != pending {
+            return Err(Error::InvalidRandomnessRequest);
         }
-        let winner_index = (random_seed % tickets.len() as u64) as u32;
-        let winner = tickets
-            .get(winner_index)
-            .expect("Ticket out of bounds callback");
 
-        raffle.status = RaffleStatus::Finalized;
-        raffle.winner = Some(winner.clone());
-        write_raffle(&env, &raffle);
+        // The oracle signs `request_id || output`; an invalid signature traps the call.
+        // This only authenticates the response: the oracle picks `output` itself and
+        // is trusted not to bias the draw.
+        let public_key = raffle
+            .oracle_public_key
+            .clone()
+            .ok_or(Error::NotAuthorized)?;
+        let mut message = Bytes::from_array(&env, &request_id.to_array());
+        message.append(&Bytes::from_array(&env, &output.to_array()));
+        env.crypto()
+            .ed25519_verify(&public_key, &message, &signature);
+
+        env.storage().instance().remove(&DataKey::RandomnessRequest);
+        env.storage().instance().remove(&DataKey::OracleDeadline);
+        let seed = seed_from_digest(&output);
 
         publish_event(
             &env,
             "randomness_received",
             RandomnessReceived {
                 oracle: raffle.oracle_address.clone().unwrap(),
-                seed: random_seed,
+                request_id,
+                seed,
                 timestamp: env.ledger().timestamp(),
             },
         );
 
+        complete_draw(
+            &env,
+            &mut raffle,
+            Entropy::Seed(seed),
+            RandomnessSource::External,
+        )
+    }
+
+    pub fn trigger_oracle_fallback(env: Env) -> Result<(), Error> {
+        let mut raffle = read_raffle(&env)?;
+
+        if raffle.status != RaffleStatus::Drawing
+            || raffle.randomness_source != RandomnessSource::External
+        {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        let request_id: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::RandomnessRequest)
+            .ok_or(Error::InvalidRandomnessRequest)?;
+        let deadline: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OracleDeadline)
+            .ok_or(Error::InvalidStateTransition)?;
+        if env.ledger().timestamp() <= deadline {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        // A refund is safe for anyone to trigger, but a PRNG draw would let ticket
+        // holders retry until it favours them, so only the creator may run it.
+        // Once the grace period lapses anyone may refund, so an absent creator
+        // cannot leave the raffle stuck in Drawing.
+        let fallback = if env.ledger().timestamp() > deadline.saturating_add(ORACLE_REFUND_GRACE) {
+            OracleFallback::Refund
+        } else {
+            raffle.oracle_fallback.clone()
+        };
+        if fallback == OracleFallback::Prng {
+            raffle.creator.require_auth();
+        }
+
+        // Drop the request so a late oracle response can no longer land.
+        env.storage().instance().remove(&DataKey::RandomnessRequest);
+        env.storage().instance().remove(&DataKey::OracleDeadline);
+
         publish_event(
             &env,
-            "raffle_finalized",
-            RaffleFinalized {
-                winner: winner.clone(),
-                winning_ticket_id: winner_index,
-                total_tickets_sold: raffle.tickets_sold,
-                randomness_source: RandomnessSource::External,
-                finalized_at: env.ledger().timestamp(),
+            "oracle_timed_out",
+            OracleTimedOut {
+                oracle: raffle.oracle_address.clone().unwrap(),
+                request_id,
+                deadline,
+                fallback: fallback.clone(),
+                timestamp: env.ledger().timestamp(),
             },
         );
 
+        match fallback {
+            OracleFallback::Prng => {
+                complete_draw(&env, &mut raffle, Entropy::Prng, RandomnessSource::Prng)?;
+            }
+            OracleFallback::Refund => {
+                cancel_with_reason(&env, &mut raffle, "Oracle timed out");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_randomness_request(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::RandomnessRequest)
+    }
+
+    pub fn reveal_secret(env: Env, secret: Bytes) -> Result<Address, Error> {
+        let mut raffle = read_raffle(&env)?;
+        raffle.creator.require_auth();
+
+        if raffle.status != RaffleStatus::Drawing
+            || raffle.randomness_source != RandomnessSource::CommitReveal
+        {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        let deadline: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RevealDeadline)
+            .ok_or(Error::InvalidStateTransition)?;
+        if env.ledger().timestamp() > deadline {
+            return Err(Error::RevealWindowClosed);
+        }
+
+        let commitment: BytesN<32> = env.crypto().sha256(&secret).into();
+        if Some(commitment) != raffle.commitment {
+            return Err(Error::InvalidReveal);
+        }
+
+        // Ledger data is pinned at draw time so the reveal moment cannot be ground.
+        let draw_sequence: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DrawSequence)
+            .unwrap_or(0);
+        let entropy: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::DrawEntropy)
+            .ok_or(Error::InvalidStateTransition)?;
+        let mut seed_src = secret.clone();
+        seed_src.append(&draw_sequence.to_xdr(&env));
+        seed_src.append(&raffle.tickets_sold.to_xdr(&env));
+        seed_src.append(&Bytes::from(entropy));
+        let seed = seed_from_digest(&env.crypto().sha256(&seed_src).into());
+
+        release_reveal_bond(&env, &raffle, &raffle.creator);
+        env.storage().instance().remove(&DataKey::RevealDeadline);
+        env.storage().instance().remove(&DataKey::DrawEntropy);
+
         publish_event(
             &env,
-            "status_changed",
-            StatusChanged {
-                old_status: RaffleStatus::Drawing,
-                new_status: RaffleStatus::Finalized,
+            "secret_revealed",
+            SecretRevealed {
+                creator: raffle.creator.clone(),
+                seed,
                 timestamp: env.ledger().timestamp(),
             },
         );
 
-        Ok(winner)
+        complete_draw(
+            &env,
+            &mut raffle,
+            Entropy::Seed(seed),
+            RandomnessSource::CommitReveal,
+        )
+    }
+
+    pub fn expire_reveal(env: Env) -> Result<(), Error> {
+        let mut raffle = read_raffle(&env)?;
+
+        if raffle.status != RaffleStatus::Drawing
+            || raffle.randomness_source != RandomnessSource::CommitReveal
+        {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        let deadline: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RevealDeadline)
+            .ok_or(Error::InvalidStateTransition)?;
+        if env.ledger().timestamp() <= deadline {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        if let Some(treasury) = raffle.treasury_address.as_ref() {
+            release_reveal_bond(&env, &raffle, treasury);
+        }
+        env.storage().instance().remove(&DataKey::RevealDeadline);
+        env.storage().instance().remove(&DataKey::DrawEntropy);
+
+        cancel_with_reason(&env, &mut raffle, "Reveal deadline missed");
+        Ok(())
     }
 
     pub fn claim_prize(env: Env, winner: Address) -> Result<i128, Error> {
@@ -522,56 +1673,182 @@ impl Contract {
         if raffle.status != RaffleStatus::Finalized {
             return Err(Error::InvalidStateTransition);
         }
-        if raffle.winner != Some(winner.clone()) {
+        if !raffle.prize_deposited && raffle.pot_bp == 0 {
+            return Err(Error::PrizeNotDeposited);
+        }
+
+        let mut winners = read_winners(&env);
+        let mut is_winner = false;
+        let mut claimed_any = false;
+        let mut total_net = 0i128;
+        let mut all_claimed = true;
+        let claimed_at = env.ledger().timestamp();
+
+        let contract_address = env.current_contract_address();
+
+        for i in 0..winners.len() {
+            let mut entry = winners.get(i).unwrap();
+            if entry.winner != winner {
+                all_claimed &= entry.claimed;
+                continue;
+            }
+            is_winner = true;
+            if entry.claimed {
+                continue;
+            }
+
+            // An NFT cannot be split, so it carries no prize-side fee.
+            let mut platform_fee = 0i128;
+            if raffle.protocol_fee_bp > 0 && raffle.prize_kind == PrizeKind::Token {
+                platform_fee = entry
+                    .amount
+                    .checked_mul(raffle.protocol_fee_bp as i128)
+                    .ok_or(Error::ArithmeticOverflow)?
+                    / 10000;
+            }
+            let net_amount = entry.amount - platform_fee;
+
+            // Transfer net prize to winner
+            transfer_prize(&env, &raffle, &contract_address, &winner, net_amount);
+
+            // Transfer fee to treasury if applicable
+            if let Some(treasury) = raffle.treasury_address.as_ref() {
+                if platform_fee > 0 {
+                    transfer_prize(&env, &raffle, &contract_address, treasury, platform_fee);
+                }
+            }
+
+            publish_event(
+                &env,
+                "prize_claimed",
+                PrizeClaimed {
+                    winner: winner.clone(),
+                    tier: entry.tier,
+                    token: raffle.prize_token.clone(),
+                    nft_token_id: nft_token_id(&raffle),
+                    gross_amount: entry.amount,
+                    net_amount,
+                    platform_fee,
+                    claimed_at,
+                },
+            );
+
+            // Bundled components ride with the top prize, each with its own fee.
+            if entry.tier == 0 {
+                for component in raffle.prize_bundle.iter() {
+                    let token_client = token::Client::new(&env, &component.token);
+                    let component_fee = component
+                        .amount
+                        .checked_mul(raffle.protocol_fee_bp as i128)
+                        .ok_or(Error::ArithmeticOverflow)?
+                        / 10000;
+                    let component_net = component.amount - component_fee;
+
+                    token_client.transfer(&contract_address, &winner, &component_net);
+                    if let Some(treasury) = raffle.treasury_address.as_ref() {
+                        if component_fee > 0 {
+                            token_client.transfer(&contract_address, treasury, &component_fee);
+                        }
+                    }
+
+                    publish_event(
+                        &env,
+                        "prize_claimed",
+                        PrizeClaimed {
+                            winner: winner.clone(),
+                            tier: entry.tier,
+                            token: component.token,
+                            nft_token_id: None,
+                            gross_amount: component.amount,
+                            net_amount: component_net,
+                            platform_fee: component_fee,
+                            claimed_at,
+                        },
+                    );
+                }
+            }
+
+            entry.claimed = true;
+            winners.set(i, entry);
+            claimed_any = true;
+            total_net += net_amount;
+        }
+
+        if !is_winner {
             return Err(Error::NotWinner);
         }
-        if !raffle.prize_deposited {
-            return Err(Error::PrizeNotDeposited);
+        if !claimed_any {
+            return Err(Error::PrizeAlreadyClaimed);
         }
+        write_winners(&env, &winners);
+
+        if all_claimed {
+            raffle.status = RaffleStatus::Claimed;
+            write_raffle(&env, &raffle);
 
+            publish_event(
+                &env,
+                "status_changed",
+                StatusChanged {
+                    old_status: RaffleStatus::Finalized,
+                    new_status: RaffleStatus::Claimed,
+                    timestamp: claimed_at,
+                },
+            );
+        }
+
+        Ok(total_net)
+    }
+
+    pub fn withdraw_proceeds(env: Env) -> Result<i128, Error> {
+        let mut raffle = read_raffle(&env)?;
+        raffle.creator.require_auth();
+
+        if raffle.status != RaffleStatus::Finalized && raffle.status != RaffleStatus::Claimed {
+            return Err(Error::InvalidStateTransition);
+        }
+        if raffle.proceeds_withdrawn {
+            return Err(Error::ProceedsAlreadyWithdrawn);
+        }
+
+        // The pot share of sales already went to the winners.
+        let gross_amount = ticket_revenue(&raffle)? - pot_share(&raffle)?;
         let mut platform_fee = 0i128;
-        if raffle.protocol_fee_bp > 0 {
-            platform_fee = (raffle.prize_amount * raffle.protocol_fee_bp as i128) / 10000;
+        if raffle.protocol_fee_bp > 0 && raffle.treasury_address.is_some() {
+            platform_fee = gross_amount
+                .checked_mul(raffle.protocol_fee_bp as i128)
+                .ok_or(Error::ArithmeticOverflow)?
+                / 10000;
         }
-        let net_amount = raffle.prize_amount - platform_fee;
-        let claimed_at = env.ledger().timestamp();
+        let net_amount = gross_amount - platform_fee;
 
         let token_client = token::Client::new(&env, &raffle.payment_token);
         let contract_address = env.current_contract_address();
 
-        // Transfer net prize to winner
-        token_client.transfer(&contract_address, &winner, &net_amount);
+        // Transfer net ticket revenue to creator
+        if net_amount > 0 {
+            token_client.transfer(&contract_address, &raffle.creator, &net_amount);
+        }
 
-        // Transfer fee to treasury if applicable
-        if platform_fee > 0 && raffle.treasury_address.is_some() {
-            token_client.transfer(
-                &contract_address,
-                &raffle.treasury_address.clone().unwrap(),
-                &platform_fee,
-            );
+        // Transfer sales-side fee to treasury if applicable
+        if let Some(treasury) = raffle.treasury_address.as_ref() {
+            if platform_fee > 0 {
+                token_client.transfer(&contract_address, treasury, &platform_fee);
+            }
         }
 
-        raffle.status = RaffleStatus::Claimed;
+        raffle.proceeds_withdrawn = true;
         write_raffle(&env, &raffle);
 
         publish_event(
             &env,
-            "prize_claimed",
-            PrizeClaimed {
-                winner: winner.clone(),
-                gross_amount: raffle.prize_amount,
+            "proceeds_withdrawn",
+            ProceedsWithdrawn {
+                creator: raffle.creator.clone(),
+                gross_amount,
                 net_amount,
                 platform_fee,
-                claimed_at,
-            },
-        );
-
-        publish_event(
-            &env,
-            "status_changed",
-            StatusChanged {
-                old_status: RaffleStatus::Finalized,
-                new_status: RaffleStatus::Claimed,
+                token: raffle.payment_token.clone(),
                 timestamp: env.ledger().timestamp(),
             },
         );
@@ -586,49 +1863,82 @@ impl Contract {
         if raffle.status == RaffleStatus::Finalized
             || raffle.status == RaffleStatus::Claimed
             || raffle.status == RaffleStatus::Cancelled
+            || raffle.status == RaffleStatus::Failed
         {
             return Err(Error::InvalidStateTransition);
         }
 
-        let old_status = raffle.status.clone();
-        raffle.status = RaffleStatus::Cancelled;
+        // A committed creator must either reveal or forfeit the bond via expire_reveal.
+        if raffle.status == RaffleStatus::Drawing
+            && raffle.randomness_source == RandomnessSource::CommitReveal
+        {
+            return Err(Error::InvalidStateTransition);
+        }
 
-        if raffle.prize_deposited {
-            let token_client = token::Client::new(&env, &raffle.payment_token);
-            let contract_address = env.current_contract_address();
-            token_client.transfer(&contract_address, &raffle.creator, &raffle.prize_amount);
-            raffle.prize_deposited = false;
+        release_reveal_bond(&env, &raffle, &raffle.creator);
+        cancel_with_reason(&env, &mut raffle, "Creator cancelled");
+
+        Ok(())
+    }
+
+    pub fn claim_refund(env: Env, buyer: Address) -> Result<i128, Error> {
+        buyer.require_auth();
+        let raffle = read_raffle(&env)?;
+
+        if !refunds_open(&raffle) {
+            return Err(Error::InvalidStateTransition);
         }
 
-        write_raffle(&env, &raffle);
+        let amount = refund_buyer(&env, &raffle, &read_tickets(&env), &buyer)?;
+        if amount == 0 {
+            return Err(Error::NoRefundAvailable);
+        }
 
-        publish_event(
-            &env,
-            "raffle_cancelled",
-            RaffleCancelled {
-                creator: raffle.creator.clone(),
-                reason: String::from_str(&env, "Creator cancelled"),
-                tickets_sold: raffle.tickets_sold,
-                timestamp: env.ledger().timestamp(),
-            },
-        );
+        Ok(amount)
+    }
 
-        publish_event(
-            &env,
-            "status_changed",
-            StatusChanged {
-                old_status,
-                new_status: RaffleStatus::Cancelled,
-                timestamp: env.ledger().timestamp(),
-            },
-        );
+    pub fn claim_sponsor_refund(env: Env, sponsor: Address) -> Result<i128, Error> {
+        sponsor.require_auth();
+        let mut raffle = read_raffle(&env)?;
 
-        Ok(())
+        if !refunds_open(&raffle) {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        refund_sponsor(&env, &mut raffle, &sponsor)
+    }
+
+    pub fn refund_batch(env: Env, buyers: Vec<Address>) -> Result<i128, Error> {
+        let raffle = read_raffle(&env)?;
+        raffle.creator.require_auth();
+
+        if !refunds_open(&raffle) {
+            return Err(Error::InvalidStateTransition);
+        }
+
+        let tickets = read_tickets(&env);
+        let mut total_refunded = 0i128;
+        for buyer in buyers.iter() {
+            let amount = refund_buyer(&env, &raffle, &tickets, &buyer)?;
+            total_refunded = total_refunded
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        Ok(total_refunded)
     }
 
     pub fn get_raffle(env: Env) -> Result<Raffle, Error> {
         read_raffle(&env)
     }
+
+    pub fn get_winners(env: Env) -> Vec<PrizeWinner> {
+        read_winners(&env)
+    }
+
+    pub fn get_sponsorship(env: Env, sponsor: Address) -> i128 {
+        read_sponsorship(&env, &sponsor)
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,82 @@
 #![cfg(test)]
 
 use super::*;
+use crate::{RaffleFactory, RaffleFactoryClient};
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::{
-    testutils::{Address as _, Events, Ledger},
-    token, Address, Env, IntoVal, String, Symbol,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, String,
 };
 
+/// HELPER: Minimal SEP-50 style NFT used to exercise NFT prize escrow
+#[contract]
+pub struct MockNft;
+
+#[contractimpl]
+impl MockNft {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage().persistent().get(&token_id).unwrap()
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        let owner: Address = env.storage().persistent().get(&token_id).unwrap();
+        assert_eq!(owner, from, "not the owner");
+        env.storage().persistent().set(&token_id, &to);
+    }
+}
+
+/// HELPER: Deterministic oracle signing key
+fn oracle_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+/// HELPER: Oracle response signature over `request_id || output`
+fn sign_randomness(env: &Env, request_id: &BytesN<32>, output: &BytesN<32>) -> BytesN<64> {
+    let mut message = request_id.to_array().to_vec();
+    message.extend_from_slice(&output.to_array());
+    BytesN::from_array(env, &oracle_signing_key().sign(&message).to_bytes())
+}
+
+/// HELPER: Baseline single-prize token raffle; tests override only what they exercise
+fn default_config(env: &Env, token: &Address) -> RaffleConfig {
+    RaffleConfig {
+        description: String::from_str(env, "Audit Raffle"),
+        start_time: 0,
+        end_time: 0,
+        max_tickets: 5,
+        allow_multiple: false,
+        max_tickets_per_address: 0,
+        ticket_price: 10i128,
+        payment_token: token.clone(),
+        prize_token: token.clone(),
+        prize_kind: PrizeKind::Token,
+        prize_nft_id: 0,
+        prize_amount: 100i128,
+        prize_bundle: Vec::new(env),
+        prize_tiers: Vec::new(env),
+        pot_bp: 0,
+        min_tickets: 0,
+        allowlist_root: None,
+        gate_token: None,
+        min_balance: 0,
+        randomness_source: RandomnessSource::Internal,
+        oracle_address: None,
+        oracle_public_key: None,
+        oracle_timeout: 0,
+        oracle_fallback: OracleFallback::Refund,
+        commitment: None,
+        reveal_window: 0,
+        reveal_bond: 0,
+        protocol_fee_bp: 0,
+        treasury_address: None,
+    }
+}
+
 /// HELPER: Standardized environment setup
 fn setup_raffle_env(
     env: &Env,
@@ -19,11 +90,41 @@ fn setup_raffle_env(
     Address,
     token::StellarAssetClient<'_>,
     Address,
+) {
+    setup_raffle_with(env, |config| {
+        config.randomness_source = source;
+        config.oracle_address = oracle;
+        config.oracle_public_key = Some(BytesN::from_array(
+            env,
+            &oracle_signing_key().verifying_key().to_bytes(),
+        ));
+        config.protocol_fee_bp = fee_bp;
+        config.treasury_address = treasury;
+    })
+}
+
+/// HELPER: Environment setup with a customised raffle config
+fn setup_raffle_with(
+    env: &Env,
+    configure: impl FnOnce(&mut RaffleConfig),
+) -> (
+    ContractClient<'_>,
+    Address,
+    Address,
+    token::StellarAssetClient<'_>,
+    Address,
 ) {
     let creator = Address::generate(env);
     let buyer = Address::generate(env);
     let admin = Address::generate(env);
-    let factory = Address::generate(env);
+
+    let factory = env.register(RaffleFactory, ());
+    RaffleFactoryClient::new(env, &factory).init(
+        &admin,
+        &BytesN::from_array(env, &[0u8; 32]),
+        &0u32,
+        &admin,
+    );
 
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_id = token_contract.address();
@@ -35,19 +136,8 @@ fn setup_raffle_env(
     let contract_id = env.register(Contract, ());
     let client = ContractClient::new(env, &contract_id);
 
-    let config = RaffleConfig {
-        description: String::from_str(env, "Audit Raffle"),
-        end_time: 0,
-        max_tickets: 5,
-        allow_multiple: false,
-        ticket_price: 10i128,
-        payment_token: token_id,
-        prize_amount: 100i128,
-        randomness_source: source,
-        oracle_address: oracle,
-        protocol_fee_bp: fee_bp,
-        treasury_address: treasury,
-    };
+    let mut config = default_config(env, &token_id);
+    configure(&mut config);
 
     client.init(&factory, &creator, &config);
 
@@ -72,7 +162,7 @@ fn test_basic_internal_raffle_flow() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
     let raffle = client.get_raffle();
     let winner = raffle.winner.unwrap();
@@ -87,7 +177,7 @@ fn test_protocol_fees() {
     let env = Env::default();
     env.mock_all_auths();
     let treasury = Address::generate(&env);
-    let (client, _creator, _buyer, admin_client, _) = setup_raffle_env(
+    let (client, creator, _buyer, admin_client, _) = setup_raffle_env(
         &env,
         RandomnessSource::Internal,
         None,
@@ -103,7 +193,7 @@ fn test_protocol_fees() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
     let winner = client.get_raffle().winner.unwrap();
     client.claim_prize(&winner);
 
@@ -123,7 +213,7 @@ fn test_vrf_raffle_flow() {
     impl DummyOracle {}
     let oracle = env.register(DummyOracle, ());
 
-    let (client, _, _buyer, admin_client, _) = setup_raffle_env(
+    let (client, creator, _buyer, admin_client, _) = setup_raffle_env(
         &env,
         RandomnessSource::External,
         Some(oracle.clone()),
@@ -141,17 +231,21 @@ fn test_vrf_raffle_flow() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
     let raffle_pre = client.get_raffle();
     assert!(matches!(raffle_pre.status, RaffleStatus::Drawing));
 
-    let seed = 12345u64;
+    let request_id = client.get_randomness_request().unwrap();
+    let output = BytesN::from_array(&env, &[9u8; 32]);
+    let signature = sign_randomness(&env, &request_id, &output);
+
+    let seed = u64::from_be_bytes([9u8; 8]);
     let expected_winner_idx = (seed % 5) as u32;
     let expected_winner = buyers.get(expected_winner_idx).unwrap();
 
     env.as_contract(&oracle, || {
-        client.provide_randomness(&seed);
+        client.provide_randomness(&request_id, &output, &signature);
     });
 
     let raffle_post = client.get_raffle();
@@ -216,37 +310,24 @@ fn test_raffle_created_event() {
     let contract_id = env.register(Contract, ());
     let client = ContractClient::new(&env, &contract_id);
 
-    let config = RaffleConfig {
-        description: String::from_str(&env, "Test Raffle"),
-        end_time: 0,
-        max_tickets: 5,
-        allow_multiple: false,
-        ticket_price: 10i128,
-        payment_token: token_id,
-        prize_amount: 100i128,
-        randomness_source: RandomnessSource::Internal,
-        oracle_address: None,
-        protocol_fee_bp: 0,
-        treasury_address: None,
-    };
+    let config = default_config(&env, &token_id);
 
     client.init(&factory, &creator, &config);
 
     // Check that raffle_created event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
 fn test_prize_deposited_event() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _, _) =
-        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let (client, _, _, _, _) = setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
 
     client.deposit_prize();
 
     // Check that prize_deposited event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -259,7 +340,7 @@ fn test_raffle_finalized_event_audit() {
         l.timestamp = expected_timestamp;
     });
 
-    let (client, _, _, admin_client, _) =
+    let (client, creator, _, admin_client, _) =
         setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
 
     client.deposit_prize();
@@ -269,10 +350,16 @@ fn test_raffle_finalized_event_audit() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
-    // Check that raffle_finalized event was emitted
-    assert!(env.events().all().len() > 0);
+    // raffle_finalized reports the same 1-based ticket ID as winner_drawn
+    let events = env.events().all();
+    let drawn: WinnerDrawn = events.get(events.len() - 3).unwrap().2.into_val(&env);
+    let finalized: RaffleFinalized = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(finalized.winning_ticket_id, drawn.ticket_id);
+    assert_eq!(finalized.winner, drawn.winner);
+    assert!((1..=5).contains(&finalized.winning_ticket_id));
+    assert_eq!(finalized.finalized_at, expected_timestamp);
 }
 
 #[test]
@@ -290,7 +377,7 @@ fn test_single_ticket_purchase_event() {
     client.buy_ticket(&buyer);
 
     // Check that ticket_purchased event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -298,7 +385,7 @@ fn test_draw_triggered_event() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, _, _, admin_client, _) =
+    let (client, creator, _, admin_client, _) =
         setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
 
     client.deposit_prize();
@@ -308,10 +395,10 @@ fn test_draw_triggered_event() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
     // Check that draw_triggered event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -325,7 +412,7 @@ fn test_randomness_requested_event() {
     impl DummyOracle {}
     let oracle = env.register(DummyOracle, ());
 
-    let (client, _, _, admin_client, _) = setup_raffle_env(
+    let (client, creator, _, admin_client, _) = setup_raffle_env(
         &env,
         RandomnessSource::External,
         Some(oracle.clone()),
@@ -340,10 +427,10 @@ fn test_randomness_requested_event() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
     // Check that randomness_requested event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -357,7 +444,7 @@ fn test_randomness_received_event() {
     impl DummyOracle {}
     let oracle = env.register(DummyOracle, ());
 
-    let (client, _, _, admin_client, _) = setup_raffle_env(
+    let (client, creator, _, admin_client, _) = setup_raffle_env(
         &env,
         RandomnessSource::External,
         Some(oracle.clone()),
@@ -372,14 +459,17 @@ fn test_randomness_received_event() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
 
+    let request_id = client.get_randomness_request().unwrap();
+    let output = BytesN::from_array(&env, &[3u8; 32]);
+    let signature = sign_randomness(&env, &request_id, &output);
     env.as_contract(&oracle, || {
-        client.provide_randomness(&12345u64);
+        client.provide_randomness(&request_id, &output, &signature);
     });
 
     // Check that randomness_received event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -387,7 +477,7 @@ fn test_prize_claimed_event() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, _, _, admin_client, _) =
+    let (client, creator, _, admin_client, _) =
         setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
 
     client.deposit_prize();
@@ -397,12 +487,12 @@ fn test_prize_claimed_event() {
         client.buy_ticket(&b);
     }
 
-    client.finalize_raffle();
+    client.finalize_raffle(&creator);
     let winner = client.get_raffle().winner.unwrap();
     client.claim_prize(&winner);
 
     // Check that prize_claimed event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -418,7 +508,7 @@ fn test_raffle_cancelled_event() {
     client.cancel_raffle();
 
     // Check that raffle_cancelled event was emitted
-    assert!(env.events().all().len() > 0);
+    assert!(!env.events().all().is_empty());
 }
 
 #[test]
@@ -426,14 +516,14 @@ fn test_status_changed_events() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, _, _, admin_client, _) =
+    let (client, _, _, _admin_client, _) =
         setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
 
     client.deposit_prize();
 
     let events_after_deposit = env.events().all();
     // Check that status_changed event was emitted
-    assert!(events_after_deposit.len() > 0);
+    assert!(!events_after_deposit.is_empty());
 }
 
 #[test]
@@ -454,3 +544,1734 @@ fn test_raffle_cancellation() {
     let raffle = client.get_raffle();
     assert!(raffle.status == RaffleStatus::Cancelled);
 }
+
+#[test]
+fn test_withdraw_proceeds_with_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let (client, creator, _buyer, admin_client, _) = setup_raffle_env(
+        &env,
+        RandomnessSource::Internal,
+        None,
+        500,
+        Some(treasury.clone()),
+    ); // 5% fee
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    // Revenue: 50, Fee: 5% = 2 (rounded down), Creator: 48
+    let net = client.withdraw_proceeds();
+    assert_eq!(net, 48i128);
+    assert_eq!(token_client.balance(&creator), 948i128);
+    assert_eq!(token_client.balance(&treasury), 2i128);
+    assert!(client.get_raffle().proceeds_withdrawn);
+
+    let winner = client.get_raffle().winner.unwrap();
+    client.claim_prize(&winner);
+    assert_eq!(token_client.balance(&client.address), 0i128);
+}
+
+#[test]
+fn test_withdraw_proceeds_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+    client.withdraw_proceeds();
+
+    assert_eq!(
+        client.try_withdraw_proceeds(),
+        Err(Ok(Error::ProceedsAlreadyWithdrawn))
+    );
+}
+
+#[test]
+fn test_withdraw_proceeds_before_finalize_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+
+    assert_eq!(
+        client.try_withdraw_proceeds(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_claim_refund_after_cancellation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+    client.cancel_raffle();
+
+    assert_eq!(token_client.balance(&buyer), 990i128);
+    assert_eq!(client.claim_refund(&buyer), 10i128);
+    assert_eq!(token_client.balance(&buyer), 1000i128);
+
+    // Count is zeroed, so a second claim has nothing to pay out
+    assert_eq!(
+        client.try_claim_refund(&buyer),
+        Err(Ok(Error::NoRefundAvailable))
+    );
+}
+
+#[test]
+fn test_claim_refund_requires_cancellation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+
+    assert_eq!(
+        client.try_claim_refund(&buyer),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_refund_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    let mut buyers = Vec::new(&env);
+    for _ in 0..3 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+        buyers.push_back(b);
+    }
+    client.cancel_raffle();
+
+    // One buyer pulls their own refund first; the batch must skip them
+    client.claim_refund(&buyers.get(0).unwrap());
+    buyers.push_back(buyer.clone());
+
+    assert_eq!(client.refund_batch(&buyers), 20i128);
+    for b in buyers.iter() {
+        assert_eq!(token_client.balance(&b), if b == buyer { 1000 } else { 10 });
+    }
+    assert_eq!(token_client.balance(&client.address), 0i128);
+}
+
+#[test]
+fn test_buy_tickets_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+        config.max_tickets = 10;
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    assert_eq!(client.buy_tickets(&buyer, &3), 3);
+    assert_eq!(client.buy_tickets(&buyer, &2), 5);
+
+    let event = env.events().all().last().unwrap();
+    let purchase: TicketPurchased = event.2.into_val(&env);
+    assert_eq!(purchase.quantity, 2);
+    assert_eq!(purchase.total_paid, 20i128);
+    assert_eq!(purchase.ticket_ids, vec![&env, 4u32, 5u32]);
+
+    // One transfer of ticket_price * quantity per call
+    assert_eq!(token_client.balance(&buyer), 950i128);
+    assert_eq!(client.get_raffle().tickets_sold, 5);
+}
+
+#[test]
+fn test_buy_tickets_fills_raffle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+    });
+
+    client.deposit_prize();
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &6),
+        Err(Ok(Error::InsufficientTickets))
+    );
+
+    client.buy_tickets(&buyer, &5);
+    assert_eq!(client.get_raffle().status, RaffleStatus::Drawing);
+}
+
+#[test]
+fn test_buy_tickets_respects_allow_multiple() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+
+    client.deposit_prize();
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &2),
+        Err(Ok(Error::MultipleTicketsNotAllowed))
+    );
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &0),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+#[test]
+fn test_buy_tickets_price_overflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+        config.max_tickets = u32::MAX;
+        config.ticket_price = i128::MAX / 2;
+    });
+
+    client.deposit_prize();
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &3),
+        Err(Ok(Error::ArithmeticOverflow))
+    );
+}
+
+#[test]
+fn test_global_pause_blocks_sales_and_draws() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, buyer, admin_client, factory) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let factory_client = RaffleFactoryClient::new(&env, &factory);
+
+    factory_client.pause();
+    assert_eq!(client.try_deposit_prize(), Err(Ok(Error::ContractPaused)));
+
+    factory_client.unpause();
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+
+    factory_client.pause();
+    let late_buyer = Address::generate(&env);
+    admin_client.mint(&late_buyer, &10i128);
+    assert_eq!(
+        client.try_buy_ticket(&late_buyer),
+        Err(Ok(Error::ContractPaused))
+    );
+    assert_eq!(
+        client.try_finalize_raffle(&creator),
+        Err(Ok(Error::ContractPaused))
+    );
+}
+
+#[test]
+fn test_global_pause_allows_claims_and_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, factory) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let factory_client = RaffleFactoryClient::new(&env, &factory);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    factory_client.pause();
+    let winner = client.get_raffle().winner.unwrap();
+    client.claim_prize(&winner);
+    client.withdraw_proceeds();
+    assert_eq!(token_client.balance(&winner), 100i128);
+
+    // A cancelled raffle still pays refunds during an incident
+    let (client, _, buyer, _, factory) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+    RaffleFactoryClient::new(&env, &factory).pause();
+    client.cancel_raffle();
+    assert_eq!(client.claim_refund(&buyer), 10i128);
+}
+
+#[test]
+fn test_creator_pause_blocks_sales() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, buyer, _, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+
+    client.deposit_prize();
+    client.pause_sales();
+
+    let event = env.events().all().last().unwrap();
+    let paused: ContractPaused = event.2.into_val(&env);
+    assert_eq!(paused.paused_by, creator.clone());
+    assert!(client.get_raffle().sales_paused);
+    assert_eq!(
+        client.try_buy_ticket(&buyer),
+        Err(Ok(Error::ContractPaused))
+    );
+    assert_eq!(
+        client.try_pause_sales(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    client.resume_sales(&false);
+    let event = env.events().all().last().unwrap();
+    let unpaused: ContractUnpaused = event.2.into_val(&env);
+    assert_eq!(unpaused.unpaused_by, creator);
+
+    client.buy_ticket(&buyer);
+    assert_eq!(client.get_raffle().tickets_sold, 1);
+}
+
+#[test]
+fn test_resume_sales_extends_end_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (client, _, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 2_000;
+    });
+
+    client.deposit_prize();
+    client.pause_sales();
+
+    env.ledger().with_mut(|l| l.timestamp = 1_500);
+    client.resume_sales(&true);
+    assert_eq!(client.get_raffle().end_time, 2_500);
+
+    // Without the extension this purchase would be past the original end
+    env.ledger().with_mut(|l| l.timestamp = 2_200);
+    client.buy_ticket(&buyer);
+
+    client.pause_sales();
+    env.ledger().with_mut(|l| l.timestamp = 2_300);
+    client.resume_sales(&false);
+    assert_eq!(client.get_raffle().end_time, 2_500);
+}
+
+#[test]
+fn test_paused_sales_block_finalize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (client, creator, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 2_000;
+    });
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+    client.pause_sales();
+
+    // end_time passing during the pause must not let the draw run early
+    env.ledger().with_mut(|l| l.timestamp = 2_100);
+    assert_eq!(
+        client.try_finalize_raffle(&creator),
+        Err(Ok(Error::ContractPaused))
+    );
+
+    client.resume_sales(&true);
+    assert_eq!(client.get_raffle().end_time, 3_100);
+    assert_eq!(
+        client.try_finalize_raffle(&creator),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_resume_sales_requires_active_raffle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (client, _, _, _, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 2_000;
+    });
+
+    client.deposit_prize();
+    client.pause_sales();
+    client.cancel_raffle();
+
+    env.ledger().with_mut(|l| l.timestamp = 1_500);
+    assert_eq!(
+        client.try_resume_sales(&true),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+    assert_eq!(client.get_raffle().end_time, 2_000);
+}
+
+/// HELPER: Commit-reveal raffle that has sold out and entered the reveal window
+fn setup_commit_reveal_raffle<'a>(
+    env: &'a Env,
+    secret: &Bytes,
+    treasury: &Address,
+) -> (ContractClient<'a>, Address, token::StellarAssetClient<'a>) {
+    let commitment: BytesN<32> = env.crypto().sha256(secret).into();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(env, |config| {
+        config.randomness_source = RandomnessSource::CommitReveal;
+        config.commitment = Some(commitment);
+        config.reveal_window = 100;
+        config.reveal_bond = 50;
+        config.treasury_address = Some(treasury.clone());
+    });
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    (client, creator, admin_client)
+}
+
+#[test]
+fn test_commit_reveal_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let treasury = Address::generate(&env);
+    let secret = Bytes::from_slice(&env, b"creator secret");
+    let (client, creator, admin_client) = setup_commit_reveal_raffle(&env, &secret, &treasury);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    // Prize and bond are both escrowed while the draw is pending
+    assert_eq!(client.get_raffle().status, RaffleStatus::Drawing);
+    assert_eq!(token_client.balance(&creator), 850i128);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_100);
+    let winner = client.reveal_secret(&secret);
+
+    let raffle = client.get_raffle();
+    assert_eq!(raffle.status, RaffleStatus::Finalized);
+    assert_eq!(raffle.winner, Some(winner));
+    assert_eq!(token_client.balance(&creator), 900i128);
+}
+
+#[test]
+fn test_commit_reveal_seed_mixes_draw_entropy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let treasury = Address::generate(&env);
+    let secret = Bytes::from_slice(&env, b"creator secret");
+    let (client, _, _) = setup_commit_reveal_raffle(&env, &secret, &treasury);
+
+    // Entropy is sampled on-chain when the draw starts, after the commitment is fixed
+    let (draw_sequence, entropy) = env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        let draw_sequence: u32 = storage.get(&DataKey::DrawSequence).unwrap();
+        let entropy: BytesN<32> = storage.get(&DataKey::DrawEntropy).unwrap();
+        (draw_sequence, entropy)
+    });
+
+    client.reveal_secret(&secret);
+    let events = env.events().all();
+    let revealed: SecretRevealed = events.get(events.len() - 4).unwrap().2.into_val(&env);
+
+    let mut seed_src = secret.clone();
+    seed_src.append(&draw_sequence.to_xdr(&env));
+    seed_src.append(&5u32.to_xdr(&env));
+    seed_src.append(&Bytes::from(entropy));
+    assert_eq!(
+        revealed.seed,
+        seed_from_digest(&env.crypto().sha256(&seed_src).into())
+    );
+}
+
+#[test]
+fn test_anyone_can_start_commit_reveal_draw_after_sales_close() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let secret = Bytes::from_slice(&env, b"creator secret");
+    let commitment: BytesN<32> = env.crypto().sha256(&secret).into();
+    let (client, creator, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 100;
+        config.randomness_source = RandomnessSource::CommitReveal;
+        config.commitment = Some(commitment);
+        config.reveal_window = 100;
+    });
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_finalize_raffle(&stranger),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    env.ledger().set_timestamp(100);
+    client.finalize_raffle(&stranger);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, stranger.clone());
+    assert_ne!(auths[0].0, creator);
+
+    let event = env.events().all().last().unwrap();
+    let triggered: DrawTriggered = event.2.into_val(&env);
+    assert_eq!(triggered.triggered_by, stranger);
+    assert_eq!(client.get_raffle().status, RaffleStatus::Drawing);
+}
+
+#[test]
+fn test_same_call_draws_require_creator() {
+    for source in [RandomnessSource::Internal, RandomnessSource::Prng] {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, creator, buyer, _, _) = setup_raffle_with(&env, |config| {
+            config.end_time = 100;
+            config.randomness_source = source;
+        });
+
+        client.deposit_prize();
+        client.buy_ticket(&buyer);
+        env.ledger().set_timestamp(100);
+
+        // The winner is picked in this call, so a ticket holder must not be able to time it
+        assert_eq!(
+            client.try_finalize_raffle(&buyer),
+            Err(Ok(Error::NotAuthorized))
+        );
+        client.finalize_raffle(&creator);
+        assert_eq!(client.get_raffle().status, RaffleStatus::Finalized);
+    }
+}
+
+#[test]
+fn test_commit_reveal_rejects_wrong_secret() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let secret = Bytes::from_slice(&env, b"creator secret");
+    let (client, _, _) = setup_commit_reveal_raffle(&env, &secret, &treasury);
+
+    assert_eq!(
+        client.try_reveal_secret(&Bytes::from_slice(&env, b"guess")),
+        Err(Ok(Error::InvalidReveal))
+    );
+    assert_eq!(
+        client.try_cancel_raffle(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_commit_reveal_missed_deadline_forfeits_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let treasury = Address::generate(&env);
+    let secret = Bytes::from_slice(&env, b"creator secret");
+    let (client, creator, admin_client) = setup_commit_reveal_raffle(&env, &secret, &treasury);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    assert_eq!(
+        client.try_expire_reveal(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    env.ledger().with_mut(|l| l.timestamp = 1_101);
+    assert_eq!(
+        client.try_reveal_secret(&secret),
+        Err(Ok(Error::RevealWindowClosed))
+    );
+    client.expire_reveal();
+
+    // Prize goes back to the creator, the bond to the treasury, and buyers can refund
+    assert_eq!(client.get_raffle().status, RaffleStatus::Cancelled);
+    assert_eq!(token_client.balance(&creator), 950i128);
+    assert_eq!(token_client.balance(&treasury), 50i128);
+    assert_eq!(token_client.balance(&client.address), 50i128);
+}
+
+#[test]
+fn test_commit_reveal_requires_commitment() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let config = RaffleConfig {
+        randomness_source: RandomnessSource::CommitReveal,
+        reveal_window: 100,
+        ..default_config(&env, &token_id)
+    };
+
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &Address::generate(&env), &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+#[test]
+fn test_prng_raffle_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Prng, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    let mut buyers = Vec::new(&env);
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+        buyers.push_back(b);
+    }
+    client.finalize_raffle(&creator);
+
+    let raffle = client.get_raffle();
+    assert_eq!(raffle.status, RaffleStatus::Finalized);
+    let winner = raffle.winner.unwrap();
+    assert!(buyers.contains(&winner));
+
+    client.claim_prize(&winner);
+    assert_eq!(token_client.balance(&winner), 100i128);
+}
+
+#[test]
+fn test_prng_ticket_index_distribution() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+
+    const TICKETS: u32 = 7;
+    const DRAWS: u32 = 7_000;
+    let mut counts = [0u32; TICKETS as usize];
+
+    env.as_contract(&contract_id, || {
+        for _ in 0..DRAWS {
+            let index = ticket_index(&env, &Entropy::Prng, TICKETS);
+            counts[index as usize] += 1;
+        }
+    });
+
+    // Every ticket should land within 15% of the uniform expectation
+    let expected = DRAWS / TICKETS;
+    for count in counts {
+        assert!(count > expected * 85 / 100, "count {count} too low");
+        assert!(count < expected * 115 / 100, "count {count} too high");
+    }
+}
+
+/// HELPER: External raffle that has sold out and is waiting on the oracle
+fn setup_external_drawing(env: &Env, fallback: OracleFallback) -> ContractClient<'_> {
+    let (client, _, _, admin_client, _) = setup_raffle_with(env, |config| {
+        config.randomness_source = RandomnessSource::External;
+        config.oracle_address = Some(Address::generate(env));
+        config.oracle_public_key = Some(BytesN::from_array(
+            env,
+            &oracle_signing_key().verifying_key().to_bytes(),
+        ));
+        config.oracle_timeout = 3_600;
+        config.oracle_fallback = fallback;
+    });
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&Address::generate(env));
+
+    client
+}
+
+#[test]
+fn test_randomness_request_is_emitted_and_stored() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let event = env.events().all().last().unwrap();
+    let requested: RandomnessRequested = event.2.into_val(&env);
+    assert_eq!(Some(requested.request_id), client.get_randomness_request());
+}
+
+#[test]
+fn test_provide_randomness_rejects_mismatched_request() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let wrong_request = BytesN::from_array(&env, &[1u8; 32]);
+    let output = BytesN::from_array(&env, &[9u8; 32]);
+    let signature = sign_randomness(&env, &wrong_request, &output);
+
+    assert_eq!(
+        client.try_provide_randomness(&wrong_request, &output, &signature),
+        Err(Ok(Error::InvalidRandomnessRequest))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_provide_randomness_rejects_bad_signature() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let request_id = client.get_randomness_request().unwrap();
+    let output = BytesN::from_array(&env, &[9u8; 32]);
+    // Signed over a different output than the one submitted
+    let signature = sign_randomness(&env, &request_id, &BytesN::from_array(&env, &[8u8; 32]));
+
+    client.provide_randomness(&request_id, &output, &signature);
+}
+
+#[test]
+fn test_provide_randomness_rejects_replay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let request_id = client.get_randomness_request().unwrap();
+    let output = BytesN::from_array(&env, &[9u8; 32]);
+    let signature = sign_randomness(&env, &request_id, &output);
+
+    client.provide_randomness(&request_id, &output, &signature);
+    assert_eq!(client.get_randomness_request(), None);
+    assert_eq!(
+        client.try_provide_randomness(&request_id, &output, &signature),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_oracle_fallback_not_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let event = env.events().all().last().unwrap();
+    let requested: RandomnessRequested = event.2.into_val(&env);
+    assert_eq!(requested.deadline, requested.timestamp + 3_600);
+
+    env.ledger().set_timestamp(requested.deadline);
+    assert_eq!(
+        client.try_trigger_oracle_fallback(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_oracle_fallback_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Refund);
+
+    let request_id = client.get_randomness_request().unwrap();
+    env.ledger().set_timestamp(3_601);
+    // The refund fallback needs no authorization
+    env.set_auths(&[]);
+    client.trigger_oracle_fallback();
+    assert!(env.auths().is_empty());
+    env.mock_all_auths();
+
+    let events = env.events().all();
+    let timed_out: OracleTimedOut = events.get(events.len() - 4).unwrap().2.into_val(&env);
+    assert_eq!(timed_out.request_id, request_id.clone());
+    assert_eq!(timed_out.fallback, OracleFallback::Refund);
+
+    let raffle = client.get_raffle();
+    assert_eq!(raffle.status, RaffleStatus::Cancelled);
+    assert_eq!(raffle.winner, None);
+
+    // A late oracle response is rejected once the fallback has run
+    let output = BytesN::from_array(&env, &[9u8; 32]);
+    let signature = sign_randomness(&env, &request_id, &output);
+    assert_eq!(
+        client.try_provide_randomness(&request_id, &output, &signature),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_oracle_fallback_internal_draw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Prng);
+
+    let creator = client.get_raffle().creator;
+    env.ledger().set_timestamp(3_601);
+    client.trigger_oracle_fallback();
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, creator);
+    let event = env.events().all().last().unwrap();
+    let status: StatusChanged = event.2.into_val(&env);
+    assert_eq!(status.new_status, RaffleStatus::Finalized);
+
+    let raffle = client.get_raffle();
+    assert_eq!(raffle.status, RaffleStatus::Finalized);
+    assert!(raffle.winner.is_some());
+    assert_eq!(client.get_randomness_request(), None);
+}
+
+#[test]
+fn test_prng_fallback_requires_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Prng);
+
+    env.ledger().set_timestamp(3_601);
+    env.set_auths(&[]);
+    assert!(client.try_trigger_oracle_fallback().is_err());
+    assert_eq!(client.get_raffle().status, RaffleStatus::Drawing);
+}
+
+#[test]
+fn test_prng_fallback_refunds_after_grace() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup_external_drawing(&env, OracleFallback::Prng);
+
+    // With the creator gone, anyone may force a refund once the grace period lapses
+    env.ledger().set_timestamp(3_600 + ORACLE_REFUND_GRACE);
+    env.set_auths(&[]);
+    assert!(client.try_trigger_oracle_fallback().is_err());
+
+    env.ledger().set_timestamp(3_601 + ORACLE_REFUND_GRACE);
+    client.trigger_oracle_fallback();
+    assert!(env.auths().is_empty());
+
+    let events = env.events().all();
+    let timed_out: OracleTimedOut = events.get(events.len() - 4).unwrap().2.into_val(&env);
+    assert_eq!(timed_out.fallback, OracleFallback::Refund);
+    env.mock_all_auths();
+    let raffle = client.get_raffle();
+    assert_eq!(raffle.status, RaffleStatus::Cancelled);
+    assert_eq!(raffle.winner, None);
+}
+
+#[test]
+fn test_tiered_prizes_draw_distinct_winners() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.prize_tiers = vec![&env, 5_000u32, 3_000u32, 2_000u32];
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    let winners = client.get_winners();
+    assert_eq!(winners.len(), 3);
+    assert_ne!(
+        winners.get(0).unwrap().ticket_id,
+        winners.get(1).unwrap().ticket_id
+    );
+    assert_ne!(
+        winners.get(0).unwrap().ticket_id,
+        winners.get(2).unwrap().ticket_id
+    );
+    assert_ne!(
+        winners.get(1).unwrap().ticket_id,
+        winners.get(2).unwrap().ticket_id
+    );
+    assert_eq!(
+        client.get_raffle().winner,
+        Some(winners.get(0).unwrap().winner)
+    );
+
+    let expected = [50i128, 30i128, 20i128];
+    for (tier, entry) in winners.iter().enumerate() {
+        assert_eq!(entry.tier, tier as u32);
+        assert_eq!(entry.amount, expected[tier]);
+
+        client.claim_prize(&entry.winner);
+        let event = env.events().all().last().unwrap();
+        if tier < 2 {
+            let claimed: PrizeClaimed = event.2.into_val(&env);
+            assert_eq!(claimed.tier, tier as u32);
+            assert_eq!(client.get_raffle().status, RaffleStatus::Finalized);
+        }
+        assert_eq!(token_client.balance(&entry.winner), expected[tier]);
+    }
+    assert_eq!(client.get_raffle().status, RaffleStatus::Claimed);
+
+    let first = winners.get(0).unwrap().winner;
+    assert_eq!(
+        client.try_claim_prize(&first),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_tiered_prize_claim_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.prize_tiers = vec![&env, 7_000u32, 3_000u32];
+    });
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    let top = client.get_winners().get(0).unwrap().winner;
+    client.claim_prize(&top);
+    assert_eq!(
+        client.try_claim_prize(&top),
+        Err(Ok(Error::PrizeAlreadyClaimed))
+    );
+    assert_eq!(
+        client.try_claim_prize(&Address::generate(&env)),
+        Err(Ok(Error::NotWinner))
+    );
+}
+
+#[test]
+fn test_unfilled_tiers_return_to_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 100;
+        config.prize_tiers = vec![&env, 6_000u32, 4_000u32];
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+    env.ledger().set_timestamp(101);
+    client.finalize_raffle(&creator);
+
+    let winners = client.get_winners();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(winners.get(0).unwrap().winner, buyer);
+    assert_eq!(winners.get(0).unwrap().amount, 60);
+    // 1000 minted - 100 prize deposited + 40 for the empty second tier
+    assert_eq!(token_client.balance(&creator), 940);
+
+    client.claim_prize(&buyer);
+    assert_eq!(client.get_raffle().status, RaffleStatus::Claimed);
+}
+
+#[test]
+fn test_prize_tiers_must_cover_whole_prize() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let config = RaffleConfig {
+        prize_tiers: vec![&env, 5_000u32, 4_000u32],
+        ..default_config(&env, &token_id)
+    };
+
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &Address::generate(&env), &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+#[test]
+fn test_fifty_fifty_pot_without_seed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.prize_amount = 0;
+        config.pot_bp = 5_000;
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    // No seed to escrow, so sales open at creation
+    assert_eq!(client.get_raffle().status, RaffleStatus::Active);
+    assert_eq!(
+        client.try_deposit_prize(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    let winner = client.get_raffle().winner.unwrap();
+    assert_eq!(client.claim_prize(&winner), 25);
+    assert_eq!(client.withdraw_proceeds(), 25);
+    assert_eq!(token_client.balance(&creator), 1_025);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_pot_topped_up_by_seed_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.pot_bp = 5_000;
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    assert_eq!(client.get_raffle().status, RaffleStatus::Proposed);
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    assert_eq!(client.get_winners().get(0).unwrap().amount, 125);
+    let winner = client.get_raffle().winner.unwrap();
+    assert_eq!(client.claim_prize(&winner), 125);
+    assert_eq!(client.withdraw_proceeds(), 25);
+    assert_eq!(token_client.balance(&creator), 925);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_prize_in_separate_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let prize_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let (client, creator, _, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.prize_token = prize_id.clone();
+        config.protocol_fee_bp = 1_000;
+        config.treasury_address = Some(treasury.clone());
+    });
+    let payment = token::Client::new(&env, &admin_client.address);
+    let prize = token::Client::new(&env, &prize_id);
+    token::StellarAssetClient::new(&env, &prize_id).mint(&creator, &100i128);
+
+    client.deposit_prize();
+    assert_eq!(prize.balance(&client.address), 100);
+    assert_eq!(payment.balance(&client.address), 0);
+
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    let winner = client.get_raffle().winner.unwrap();
+    client.claim_prize(&winner);
+    let events = env.events().all();
+    let claimed: PrizeClaimed = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(claimed.token, prize_id.clone());
+    assert_eq!(prize.balance(&winner), 90);
+    assert_eq!(prize.balance(&treasury), 10);
+
+    // Sales-side fee is taken in the payment token
+    client.withdraw_proceeds();
+    assert_eq!(payment.balance(&creator), 1_045);
+    assert_eq!(payment.balance(&treasury), 5);
+}
+
+#[test]
+fn test_cancel_returns_prize_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let prize_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let (client, creator, _, _, _) = setup_raffle_with(&env, |config| {
+        config.prize_token = prize_id.clone();
+    });
+    let prize = token::Client::new(&env, &prize_id);
+    token::StellarAssetClient::new(&env, &prize_id).mint(&creator, &100i128);
+
+    client.deposit_prize();
+    assert_eq!(prize.balance(&creator), 0);
+    client.cancel_raffle();
+    assert_eq!(prize.balance(&creator), 100);
+}
+
+#[test]
+fn test_pot_requires_prize_in_payment_token() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let config = RaffleConfig {
+        prize_token: Address::generate(&env),
+        pot_bp: 5_000,
+        ..default_config(&env, &token_id)
+    };
+
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &Address::generate(&env), &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+fn setup_nft_raffle(env: &Env) -> (ContractClient<'_>, Address, MockNftClient<'_>) {
+    let nft = MockNftClient::new(env, &env.register(MockNft, ()));
+    let (client, creator, _, admin_client, _) = setup_raffle_with(env, |config| {
+        config.prize_token = nft.address.clone();
+        config.prize_kind = PrizeKind::Nft;
+        config.prize_nft_id = 42;
+        config.prize_amount = 1;
+        config.protocol_fee_bp = 1_000;
+    });
+    nft.mint(&creator, &42u32);
+
+    client.deposit_prize();
+    for _ in 0..5 {
+        let b = Address::generate(env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+
+    (client, creator, nft)
+}
+
+#[test]
+fn test_nft_prize_escrowed_on_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let nft = MockNftClient::new(&env, &env.register(MockNft, ()));
+    let (client, creator, _, _, _) = setup_raffle_with(&env, |config| {
+        config.prize_token = nft.address.clone();
+        config.prize_kind = PrizeKind::Nft;
+        config.prize_nft_id = 42;
+        config.prize_amount = 1;
+    });
+    nft.mint(&creator, &42u32);
+
+    client.deposit_prize();
+    let events = env.events().all();
+    let deposited: PrizeDeposited = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(deposited.token, nft.address.clone());
+    assert_eq!(deposited.nft_token_id, Some(42));
+    assert_eq!(nft.owner_of(&42u32), client.address);
+}
+
+#[test]
+fn test_nft_prize_goes_to_winner_without_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, nft) = setup_nft_raffle(&env);
+
+    client.finalize_raffle(&creator);
+    let winner = client.get_raffle().winner.unwrap();
+    client.claim_prize(&winner);
+
+    let events = env.events().all();
+    let claimed: PrizeClaimed = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(claimed.nft_token_id, Some(42));
+    assert_eq!(claimed.platform_fee, 0);
+    assert_eq!(nft.owner_of(&42u32), winner);
+}
+
+#[test]
+fn test_cancel_returns_nft_prize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, nft) = setup_nft_raffle(&env);
+
+    client.cancel_raffle();
+    assert_eq!(nft.owner_of(&42u32), creator);
+}
+
+fn setup_bundle_raffle<'a>(
+    env: &'a Env,
+    treasury: &Address,
+) -> (
+    ContractClient<'a>,
+    Address,
+    token::StellarAssetClient<'a>,
+    token::Client<'a>,
+    token::Client<'a>,
+) {
+    let xlm = token::Client::new(
+        env,
+        &env.register_stellar_asset_contract_v2(Address::generate(env))
+            .address(),
+    );
+    let usdc = token::Client::new(
+        env,
+        &env.register_stellar_asset_contract_v2(Address::generate(env))
+            .address(),
+    );
+    let (client, creator, _, admin_client, _) = setup_raffle_with(env, |config| {
+        config.prize_bundle = vec![
+            env,
+            PrizeComponent {
+                token: xlm.address.clone(),
+                amount: 100,
+            },
+            PrizeComponent {
+                token: usdc.address.clone(),
+                amount: 50,
+            },
+        ];
+        config.protocol_fee_bp = 1_000;
+        config.treasury_address = Some(treasury.clone());
+    });
+    token::StellarAssetClient::new(env, &xlm.address).mint(&creator, &100i128);
+    token::StellarAssetClient::new(env, &usdc.address).mint(&creator, &50i128);
+
+    (client, creator, admin_client, xlm, usdc)
+}
+
+#[test]
+fn test_prize_bundle_claimed_with_per_component_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let (client, creator, admin_client, xlm, usdc) = setup_bundle_raffle(&env, &treasury);
+
+    client.deposit_prize();
+    assert_eq!(xlm.balance(&client.address), 100);
+    assert_eq!(usdc.balance(&client.address), 50);
+
+    for _ in 0..5 {
+        let b = Address::generate(&env);
+        admin_client.mint(&b, &10i128);
+        client.buy_ticket(&b);
+    }
+    client.finalize_raffle(&creator);
+
+    let winner = client.get_raffle().winner.unwrap();
+    client.claim_prize(&winner);
+
+    let events = env.events().all();
+    let claimed: PrizeClaimed = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(claimed.token, usdc.address.clone());
+    assert_eq!(claimed.platform_fee, 5);
+
+    assert_eq!(xlm.balance(&winner), 90);
+    assert_eq!(xlm.balance(&treasury), 10);
+    assert_eq!(usdc.balance(&winner), 45);
+    assert_eq!(usdc.balance(&treasury), 5);
+    assert_eq!(xlm.balance(&client.address), 0);
+    assert_eq!(usdc.balance(&client.address), 0);
+}
+
+#[test]
+fn test_cancel_returns_prize_bundle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let (client, creator, _, xlm, usdc) = setup_bundle_raffle(&env, &treasury);
+
+    client.deposit_prize();
+    client.cancel_raffle();
+
+    assert_eq!(xlm.balance(&creator), 100);
+    assert_eq!(usdc.balance(&creator), 50);
+    assert!(!client.get_raffle().prize_deposited);
+}
+
+#[test]
+fn test_sponsors_fund_prize_and_activate_raffle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    admin_client.mint(&first, &100i128);
+    admin_client.mint(&second, &100i128);
+
+    assert_eq!(client.sponsor_prize(&first, &60i128), 60);
+    assert_eq!(client.get_raffle().status, RaffleStatus::Proposed);
+    assert_eq!(
+        client.try_sponsor_prize(&second, &50i128),
+        Err(Ok(Error::InvalidParameters))
+    );
+
+    assert_eq!(client.sponsor_prize(&second, &40i128), 100);
+    let event = env.events().all().last().unwrap();
+    let status: StatusChanged = event.2.into_val(&env);
+    assert_eq!(status.new_status, RaffleStatus::Active);
+
+    let raffle = client.get_raffle();
+    assert!(raffle.prize_deposited);
+    assert_eq!(client.get_sponsorship(&first), 60);
+    assert_eq!(client.get_sponsorship(&second), 40);
+    assert_eq!(token_client.balance(&creator), 1_000);
+    assert_eq!(
+        client.try_deposit_prize(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+}
+
+#[test]
+fn test_cancel_returns_sponsor_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    let sponsor = Address::generate(&env);
+    admin_client.mint(&sponsor, &30i128);
+    client.sponsor_prize(&sponsor, &30i128);
+
+    // The creator only tops up what sponsors left outstanding
+    client.deposit_prize();
+    assert_eq!(token_client.balance(&creator), 930);
+
+    // Cancelling returns the creator's part; the sponsor pulls their own share
+    client.cancel_raffle();
+    assert_eq!(token_client.balance(&creator), 1_000);
+    assert_eq!(token_client.balance(&sponsor), 0);
+    assert_eq!(
+        client.try_withdraw_sponsorship(&sponsor),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    assert_eq!(client.claim_sponsor_refund(&sponsor), 30);
+    let event = env.events().all().last().unwrap();
+    let refunded: SponsorRefunded = event.2.into_val(&env);
+    assert_eq!(refunded.sponsor, sponsor.clone());
+    assert_eq!(refunded.amount, 30);
+    assert_eq!(token_client.balance(&sponsor), 30);
+    assert_eq!(client.get_sponsorship(&sponsor), 0);
+    assert_eq!(
+        client.try_claim_sponsor_refund(&sponsor),
+        Err(Ok(Error::NoRefundAvailable))
+    );
+}
+
+#[test]
+fn test_cancel_before_fully_sponsored_refunds_sponsor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    let sponsor = Address::generate(&env);
+    admin_client.mint(&sponsor, &50i128);
+    client.sponsor_prize(&sponsor, &50i128);
+
+    assert_eq!(
+        client.try_claim_sponsor_refund(&sponsor),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+    client.cancel_raffle();
+    assert_eq!(client.claim_sponsor_refund(&sponsor), 50);
+    assert_eq!(token_client.balance(&sponsor), 50);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_sponsor_withdraws_while_proposed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, _, admin_client, _) =
+        setup_raffle_env(&env, RandomnessSource::Internal, None, 0, None);
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    let sponsor = Address::generate(&env);
+    admin_client.mint(&sponsor, &60i128);
+    client.sponsor_prize(&sponsor, &60i128);
+
+    assert_eq!(client.withdraw_sponsorship(&sponsor), 60);
+    let event = env.events().all().last().unwrap();
+    let refunded: SponsorRefunded = event.2.into_val(&env);
+    assert_eq!(refunded.sponsor, sponsor.clone());
+    assert_eq!(refunded.amount, 60);
+    assert_eq!(token_client.balance(&sponsor), 60);
+    assert_eq!(client.get_sponsorship(&sponsor), 0);
+    assert_eq!(client.get_raffle().prize_sponsored, 0);
+    assert_eq!(
+        client.try_withdraw_sponsorship(&sponsor),
+        Err(Ok(Error::NoRefundAvailable))
+    );
+
+    // Once the prize is escrowed and sales open, sponsorships are locked in
+    client.sponsor_prize(&sponsor, &30i128);
+    client.deposit_prize();
+    assert_eq!(token_client.balance(&creator), 930);
+    assert_eq!(
+        client.try_withdraw_sponsorship(&sponsor),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    // A later cancellation still refunds the sponsor exactly once
+    client.cancel_raffle();
+    assert_eq!(client.claim_sponsor_refund(&sponsor), 30);
+    assert_eq!(token_client.balance(&sponsor), 60);
+    assert_eq!(token_client.balance(&creator), 1_000);
+}
+
+#[test]
+fn test_min_tickets_not_reached_fails_raffle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 100;
+        config.min_tickets = 3;
+        config.allow_multiple = true;
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    client.buy_tickets(&buyer, &2u32);
+    env.ledger().set_timestamp(101);
+    client.finalize_raffle(&creator);
+
+    let events = env.events().all();
+    let failed: RaffleFailed = events.get(events.len() - 2).unwrap().2.into_val(&env);
+    assert_eq!(failed.tickets_sold, 2);
+    assert_eq!(failed.min_tickets, 3);
+    assert_eq!(
+        failed.reason,
+        String::from_str(&env, "Minimum tickets not reached")
+    );
+    let status: StatusChanged = events.last().unwrap().2.into_val(&env);
+    assert_eq!(status.new_status, RaffleStatus::Failed);
+
+    assert_eq!(client.get_raffle().status, RaffleStatus::Failed);
+    assert_eq!(token_client.balance(&creator), 1_000);
+    assert_eq!(
+        client.try_cancel_raffle(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    assert_eq!(client.claim_refund(&buyer), 20);
+    assert_eq!(token_client.balance(&buyer), 1_000);
+}
+
+#[test]
+fn test_min_tickets_reached_draws_normally() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, creator, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.end_time = 100;
+        config.min_tickets = 2;
+        config.allow_multiple = true;
+    });
+
+    client.deposit_prize();
+    client.buy_tickets(&buyer, &2u32);
+    env.ledger().set_timestamp(101);
+    client.finalize_raffle(&creator);
+
+    assert_eq!(client.get_raffle().status, RaffleStatus::Finalized);
+    assert_eq!(client.get_raffle().winner, Some(buyer));
+}
+
+#[test]
+fn test_max_tickets_per_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+        config.max_tickets_per_address = 3;
+    });
+
+    client.deposit_prize();
+    client.buy_tickets(&buyer, &2u32);
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &2u32),
+        Err(Ok(Error::TicketLimitExceeded))
+    );
+    client.buy_ticket(&buyer);
+    assert_eq!(
+        client.try_buy_ticket(&buyer),
+        Err(Ok(Error::TicketLimitExceeded))
+    );
+    assert_eq!(client.get_raffle().tickets_sold, 3);
+}
+
+#[test]
+fn test_sales_wait_for_start_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10);
+    let (client, _, buyer, _, _) = setup_raffle_with(&env, |config| {
+        config.start_time = 50;
+        config.end_time = 100;
+    });
+
+    // The prize can be escrowed ahead of launch
+    client.deposit_prize();
+    assert_eq!(client.get_raffle().start_time, 50);
+    assert_eq!(
+        client.try_buy_ticket(&buyer),
+        Err(Ok(Error::RaffleNotStarted))
+    );
+
+    env.ledger().set_timestamp(50);
+    assert_eq!(client.buy_ticket(&buyer), 1);
+}
+
+#[test]
+fn test_start_time_must_precede_end_time() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let config = RaffleConfig {
+        start_time: 200,
+        end_time: 100,
+        ..default_config(&env, &token_id)
+    };
+
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &Address::generate(&env), &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+fn allowlist_leaf(env: &Env, account: &Address, allocation: u32) -> BytesN<32> {
+    let mut leaf_src = account.clone().to_xdr(env);
+    leaf_src.append(&allocation.to_xdr(env));
+    env.crypto().sha256(&leaf_src).into()
+}
+
+fn allowlist_node(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a < b { (a, b) } else { (b, a) };
+    let mut pair = Bytes::from(left.clone());
+    pair.append(&Bytes::from(right.clone()));
+    env.crypto().sha256(&pair).into()
+}
+
+#[test]
+fn test_allowlist_gated_purchases() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let member = Address::generate(&env);
+    let other = Address::generate(&env);
+    let member_leaf = allowlist_leaf(&env, &member, 2);
+    let other_leaf = allowlist_leaf(&env, &other, 0);
+    let root = allowlist_node(&env, &member_leaf, &other_leaf);
+
+    let (client, _, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+        config.allowlist_root = Some(root.clone());
+    });
+    admin_client.mint(&member, &100i128);
+    admin_client.mint(&other, &100i128);
+    client.deposit_prize();
+
+    // Open purchase paths are closed on a gated raffle
+    assert_eq!(client.try_buy_ticket(&buyer), Err(Ok(Error::InvalidProof)));
+
+    let member_proof = vec![&env, other_leaf.clone()];
+    client.buy_ticket_with_proof(&member, &2u32, &2u32, &member_proof);
+    assert_eq!(
+        client.try_buy_ticket_with_proof(&member, &1u32, &2u32, &member_proof),
+        Err(Ok(Error::TicketLimitExceeded))
+    );
+    // Claiming a larger allocation than the leaf commits to fails verification
+    assert_eq!(
+        client.try_buy_ticket_with_proof(&member, &1u32, &5u32, &member_proof),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_buy_ticket_with_proof(&buyer, &1u32, &0u32, &member_proof),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    let other_proof = vec![&env, member_leaf];
+    client.buy_ticket_with_proof(&other, &3u32, &0u32, &other_proof);
+    assert_eq!(client.get_raffle().tickets_sold, 5);
+}
+
+#[test]
+fn test_token_gate_requires_min_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let gate_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let (client, _, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.gate_token = Some(gate_id.clone());
+        config.min_balance = 50;
+    });
+    let gate_admin = token::StellarAssetClient::new(&env, &gate_id);
+    client.deposit_prize();
+
+    assert_eq!(client.try_buy_ticket(&buyer), Err(Ok(Error::NotEligible)));
+    gate_admin.mint(&buyer, &49i128);
+    assert_eq!(client.try_buy_ticket(&buyer), Err(Ok(Error::NotEligible)));
+    gate_admin.mint(&buyer, &1i128);
+    assert_eq!(client.buy_ticket(&buyer), 1);
+
+    let holder = Address::generate(&env);
+    admin_client.mint(&holder, &10i128);
+    gate_admin.mint(&holder, &500i128);
+    assert_eq!(client.buy_ticket(&holder), 2);
+}
+
+#[test]
+fn test_free_entry_raffle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let (client, _, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.ticket_price = 0;
+        config.allow_multiple = true;
+        config.protocol_fee_bp = 1_000;
+        config.treasury_address = Some(treasury.clone());
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    let entrant = Address::generate(&env);
+    assert_eq!(client.buy_ticket(&entrant), 1);
+    assert_eq!(
+        client.try_buy_ticket(&entrant),
+        Err(Ok(Error::MultipleTicketsNotAllowed))
+    );
+    assert_eq!(
+        client.try_buy_tickets(&buyer, &2u32),
+        Err(Ok(Error::MultipleTicketsNotAllowed))
+    );
+    client.buy_ticket(&buyer);
+    assert_eq!(token_client.balance(&buyer), 1_000);
+
+    client.cancel_raffle();
+    assert_eq!(
+        client.try_claim_refund(&entrant),
+        Err(Ok(Error::NoRefundAvailable))
+    );
+}
+
+#[test]
+fn test_free_entry_prize_still_pays_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let treasury = Address::generate(&env);
+    let (client, creator, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.ticket_price = 0;
+        config.max_tickets = 1;
+        config.protocol_fee_bp = 1_000;
+        config.treasury_address = Some(treasury.clone());
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+
+    client.deposit_prize();
+    client.buy_ticket(&buyer);
+    client.finalize_raffle(&creator);
+
+    assert_eq!(client.claim_prize(&buyer), 90);
+    assert_eq!(token_client.balance(&treasury), 10);
+    assert_eq!(client.withdraw_proceeds(), 0);
+}
+
+#[test]
+fn test_buy_ticket_for_free_entry_requires_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, payer, _, _) = setup_raffle_with(&env, |config| {
+        config.ticket_price = 0;
+    });
+    client.deposit_prize();
+
+    // The payer alone cannot enter a recipient into a free raffle
+    let recipient = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &payer,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "buy_ticket_for",
+            args: (&payer, &recipient, 1u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client
+        .try_buy_ticket_for(&payer, &recipient, &1u32)
+        .is_err());
+
+    env.mock_all_auths();
+    client.buy_ticket_for(&payer, &recipient, &1u32);
+    let auths = env.auths();
+    assert_eq!(auths.len(), 2);
+    assert_eq!(auths[0].0, payer);
+    assert_eq!(auths[1].0, recipient);
+    assert_eq!(client.get_raffle().tickets_sold, 1);
+}
+
+#[test]
+fn test_buy_ticket_for_gifts_tickets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, buyer, admin_client, _) = setup_raffle_with(&env, |config| {
+        config.allow_multiple = true;
+    });
+    let token_client = token::Client::new(&env, &admin_client.address);
+    let recipient = Address::generate(&env);
+
+    client.deposit_prize();
+    client.buy_ticket_for(&buyer, &recipient, &2u32);
+
+    let auths = env.auths();
+    assert_eq!(auths[0].0, buyer.clone());
+    let event = env.events().all().last().unwrap();
+    let purchased: TicketPurchased = event.2.into_val(&env);
+    assert_eq!(purchased.buyer, recipient.clone());
+    assert_eq!(purchased.payer, buyer.clone());
+    assert_eq!(purchased.ticket_ids, vec![&env, 1u32, 2u32]);
+
+    assert_eq!(token_client.balance(&buyer), 980);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // Refunds follow the tickets to the recipient
+    client.cancel_raffle();
+    assert_eq!(client.claim_refund(&recipient), 20);
+    assert_eq!(
+        client.try_claim_refund(&buyer),
+        Err(Ok(Error::NoRefundAvailable))
+    );
+}
@@ -1,15 +1,36 @@
 #![no_std]
+// Each wasm build exports only one of the two contracts, leaving the other's
+// helpers unreferenced.
+#![cfg_attr(target_family = "wasm", allow(dead_code, unused_imports))]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, xdr::ToXdr, Address, Bytes, Env, String, Vec,
+    contract, contractclient, contractimpl, contracttype, token, xdr::ToXdr, Address, BytesN, Env,
+    Vec,
 };
 
 mod events;
 mod instance;
-use instance::{RaffleConfig, RandomnessSource};
+mod nft;
+use events::{
+    publish_event, AdminTransferAccepted, AdminTransferProposed, ContractPaused, ContractUnpaused,
+    FeeUpdated, FeesWithdrawn, OracleAddressUpdated, TreasuryUpdated,
+};
+use instance::{Error, RaffleConfig, RandomnessSource, MAX_FEE_BP};
 
 #[contract]
 pub struct RaffleFactory;
 
+/// Instance entry point the factory calls when deploying a new raffle.
+#[allow(dead_code)]
+#[contractclient(name = "RaffleInstanceClient")]
+pub trait RaffleInstance {
+    fn init(
+        env: Env,
+        factory: Address,
+        creator: Address,
+        config: RaffleConfig,
+    ) -> Result<(), Error>;
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -18,19 +39,110 @@ pub enum DataKey {
     InstanceWasmHash,
     ProtocolFeeBP,
     Treasury,
+    OracleAddress,
+    OraclePublicKey,
+    Paused,
+    PendingAdmin,
+}
+
+fn read_admin(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)
 }
 
+fn write_protocol_fee(env: &Env, admin: &Address, protocol_fee_bp: u32) -> Result<(), Error> {
+    if protocol_fee_bp > MAX_FEE_BP {
+        return Err(Error::InvalidParameters);
+    }
+    let old_fee_bp: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ProtocolFeeBP)
+        .unwrap_or(0);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ProtocolFeeBP, &protocol_fee_bp);
+
+    publish_event(
+        env,
+        "fee_updated",
+        FeeUpdated {
+            old_fee_bp,
+            new_fee_bp: protocol_fee_bp,
+            updated_by: admin.clone(),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    Ok(())
+}
+
+fn write_treasury(env: &Env, admin: &Address, treasury: &Address) {
+    let old_treasury: Option<Address> = env.storage().persistent().get(&DataKey::Treasury);
+    env.storage().persistent().set(&DataKey::Treasury, treasury);
+
+    publish_event(
+        env,
+        "treasury_updated",
+        TreasuryUpdated {
+            old_treasury,
+            new_treasury: treasury.clone(),
+            updated_by: admin.clone(),
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+}
+
+fn write_paused(env: &Env, admin: &Address, paused: bool) -> Result<(), Error> {
+    let currently_paused: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Paused)
+        .unwrap_or(false);
+    if currently_paused == paused {
+        return Err(Error::InvalidStateTransition);
+    }
+    env.storage().persistent().set(&DataKey::Paused, &paused);
+
+    let timestamp = env.ledger().timestamp();
+    if paused {
+        publish_event(
+            env,
+            "contract_paused",
+            ContractPaused {
+                paused_by: admin.clone(),
+                timestamp,
+            },
+        );
+    } else {
+        publish_event(
+            env,
+            "contract_unpaused",
+            ContractUnpaused {
+                unpaused_by: admin.clone(),
+                timestamp,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(all(target_family = "wasm", feature = "instance")))]
 #[contractimpl]
 impl RaffleFactory {
     pub fn init(
         env: Env,
         admin: Address,
-        wasm_hash: Bytes,
+        wasm_hash: BytesN<32>,
         protocol_fee_bp: u32,
         treasury: Address,
-    ) {
+    ) -> Result<(), Error> {
         if env.storage().persistent().has(&DataKey::Admin) {
-            panic!("already initialized");
+            return Err(Error::AlreadyInitialized);
+        }
+        if protocol_fee_bp > MAX_FEE_BP {
+            return Err(Error::InvalidParameters);
         }
         env.storage().persistent().set(&DataKey::Admin, &admin);
         env.storage()
@@ -45,35 +157,119 @@ impl RaffleFactory {
         env.storage()
             .persistent()
             .set(&DataKey::Treasury, &treasury);
+        Ok(())
+    }
+
+    pub fn set_config(env: Env, protocol_fee_bp: u32, treasury: Address) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+        write_protocol_fee(&env, &admin, protocol_fee_bp)?;
+        write_treasury(&env, &admin, &treasury);
+        Ok(())
     }
 
-    pub fn set_config(env: Env, protocol_fee_bp: u32, treasury: Address) {
-        let admin: Address = env.storage().persistent().get(&DataKey::Admin).unwrap();
+    pub fn set_protocol_fee(env: Env, protocol_fee_bp: u32) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
         admin.require_auth();
+        write_protocol_fee(&env, &admin, protocol_fee_bp)
+    }
+
+    pub fn set_treasury(env: Env, treasury: Address) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+        write_treasury(&env, &admin, &treasury);
+        Ok(())
+    }
+
+    pub fn set_oracle_address(
+        env: Env,
+        oracle: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+
+        let old_oracle: Option<Address> = env.storage().persistent().get(&DataKey::OracleAddress);
         env.storage()
             .persistent()
-            .set(&DataKey::ProtocolFeeBP, &protocol_fee_bp);
+            .set(&DataKey::OracleAddress, &oracle);
         env.storage()
             .persistent()
-            .set(&DataKey::Treasury, &treasury);
+            .set(&DataKey::OraclePublicKey, &public_key);
+
+        publish_event(
+            &env,
+            "oracle_address_updated",
+            OracleAddressUpdated {
+                old_oracle,
+                new_oracle: oracle,
+                updated_by: admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn pause(env: Env) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+        write_paused(&env, &admin, true)
+    }
+
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+        write_paused(&env, &admin, false)
+    }
+
+    // Instances pay their protocol fees (and forfeited reveal bonds) to the
+    // factory, which holds them until the admin sends them to the treasury.
+    pub fn withdraw_fees(env: Env, token: Address, amount: i128) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidParameters);
+        }
+        let recipient: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Treasury)
+            .ok_or(Error::NotInitialized)?;
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+        publish_event(
+            &env,
+            "fees_withdrawn",
+            FeesWithdrawn {
+                recipient,
+                amount,
+                token,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
     }
 
     pub fn create_raffle(
         env: Env,
         creator: Address,
-        description: String,
-        end_time: u64,
-        max_tickets: u32,
-        allow_multiple: bool,
-        ticket_price: i128,
-        payment_token: Address,
-        prize_amount: i128,
-        randomness_source: RandomnessSource,
-        oracle_address: Option<Address>,
-    ) -> Address {
+        config: RaffleConfig,
+    ) -> Result<Address, Error> {
         creator.require_auth();
 
-        let _wasm_hash: Bytes = env
+        if Self::is_paused(env.clone()) {
+            return Err(Error::ContractPaused);
+        }
+        // Fee and treasury are protocol-wide settings, filled in below. Reject
+        // creator-supplied values rather than silently replacing them.
+        if config.protocol_fee_bp != 0 || config.treasury_address.is_some() {
+            return Err(Error::InvalidParameters);
+        }
+
+        let wasm_hash: BytesN<32> = env
             .storage()
             .persistent()
             .get(&DataKey::InstanceWasmHash)
@@ -84,13 +280,6 @@ impl RaffleFactory {
             .persistent()
             .get(&DataKey::ProtocolFeeBP)
             .unwrap_or(0);
-        let treasury: Address = env.storage().persistent().get(&DataKey::Treasury).unwrap();
-
-        let mut _salt_src = Vec::new(&env);
-        _salt_src.push_back(creator.clone());
-        let _salt = env.crypto().sha256(&creator.clone().to_xdr(&env));
-
-        // Deployment logic placeholder
 
         let mut instances: Vec<Address> = env
             .storage()
@@ -98,27 +287,101 @@ impl RaffleFactory {
             .get(&DataKey::RaffleInstances)
             .unwrap();
 
-        // Use parameters to avoid warnings
-        let _ = RaffleConfig {
-            description,
-            end_time,
-            max_tickets,
-            allow_multiple,
-            ticket_price,
-            payment_token,
-            prize_amount,
-            randomness_source,
+        // External raffles without their own oracle fall back to the protocol oracle.
+        let (oracle_address, oracle_public_key) = match config.oracle_address.clone() {
+            None if config.randomness_source == RandomnessSource::External => (
+                env.storage().persistent().get(&DataKey::OracleAddress),
+                env.storage().persistent().get(&DataKey::OraclePublicKey),
+            ),
+            oracle_address => (oracle_address, config.oracle_public_key.clone()),
+        };
+
+        let config = RaffleConfig {
             oracle_address,
+            oracle_public_key,
             protocol_fee_bp,
-            treasury_address: Some(treasury),
+            treasury_address: Some(env.current_contract_address()),
+            ..config
         };
 
-        instances.push_back(creator.clone());
+        // The instance count only ever grows, so mixing it into the salt keeps
+        // every deployment address unique even for repeat creators.
+        let mut salt_src = creator.clone().to_xdr(&env);
+        salt_src.append(&instances.len().to_xdr(&env));
+        let salt = env.crypto().sha256(&salt_src);
+
+        let raffle_address = env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(wasm_hash, ());
+
+        RaffleInstanceClient::new(&env, &raffle_address).init(
+            &env.current_contract_address(),
+            &creator,
+            &config,
+        );
+
+        instances.push_back(raffle_address.clone());
         env.storage()
             .persistent()
             .set(&DataKey::RaffleInstances, &instances);
 
-        creator
+        Ok(raffle_address)
+    }
+
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        publish_event(
+            &env,
+            "admin_transfer_proposed",
+            AdminTransferProposed {
+                current_admin: admin,
+                proposed_admin: new_admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let old_admin = read_admin(&env)?;
+        let new_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(Error::InvalidStateTransition)?;
+        new_admin.require_auth();
+
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+        env.storage().persistent().remove(&DataKey::PendingAdmin);
+
+        publish_event(
+            &env,
+            "admin_transfer_accepted",
+            AdminTransferAccepted {
+                old_admin,
+                new_admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn cancel_admin_proposal(env: Env) -> Result<(), Error> {
+        let admin = read_admin(&env)?;
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::PendingAdmin) {
+            return Err(Error::InvalidStateTransition);
+        }
+        env.storage().persistent().remove(&DataKey::PendingAdmin);
+        Ok(())
     }
 
     pub fn get_raffles(env: Env) -> Vec<Address> {
@@ -127,4 +390,41 @@ impl RaffleFactory {
             .get(&DataKey::RaffleInstances)
             .unwrap_or_else(|| Vec::new(&env))
     }
+
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        read_admin(&env)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn get_protocol_fee(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProtocolFeeBP)
+            .unwrap_or(0)
+    }
+
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Treasury)
+    }
+
+    pub fn get_oracle_address(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::OracleAddress)
+    }
+
+    pub fn get_oracle_public_key(env: Env) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::OraclePublicKey)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
 }
+
+#[cfg(test)]
+mod test;
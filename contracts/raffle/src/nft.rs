@@ -0,0 +1,10 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the SEP-50 non-fungible token interface used to escrow NFT prizes.
+#[allow(dead_code)]
+#[contractclient(name = "NftClient")]
+pub trait NonFungibleToken {
+    fn owner_of(env: Env, token_id: u32) -> Address;
+
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
+}
@@ -0,0 +1,415 @@
+#![cfg(test)]
+
+use super::*;
+use crate::instance::{OracleFallback, PrizeKind};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    Address, BytesN, Env, IntoVal, String, Vec,
+};
+
+/// HELPER: Initialized factory with a 5% fee
+fn setup_factory(env: &Env) -> (RaffleFactoryClient<'_>, Address, Address) {
+    let admin = Address::generate(env);
+    let treasury = Address::generate(env);
+
+    let factory_id = env.register(RaffleFactory, ());
+    let client = RaffleFactoryClient::new(env, &factory_id);
+    client.init(
+        &admin,
+        &BytesN::from_array(env, &[0u8; 32]),
+        &500u32,
+        &treasury,
+    );
+
+    (client, admin, treasury)
+}
+
+/// HELPER: Minimal token-prize config for raffles created through the factory
+fn raffle_config(env: &Env, token: &Address) -> RaffleConfig {
+    RaffleConfig {
+        description: String::from_str(env, "Factory Raffle"),
+        start_time: 0,
+        end_time: 0,
+        max_tickets: 5,
+        allow_multiple: false,
+        max_tickets_per_address: 0,
+        ticket_price: 10i128,
+        payment_token: token.clone(),
+        prize_token: token.clone(),
+        prize_kind: PrizeKind::Token,
+        prize_nft_id: 0,
+        prize_amount: 100i128,
+        prize_bundle: Vec::new(env),
+        prize_tiers: Vec::new(env),
+        pot_bp: 0,
+        min_tickets: 0,
+        allowlist_root: None,
+        gate_token: None,
+        min_balance: 0,
+        randomness_source: RandomnessSource::Internal,
+        oracle_address: None,
+        oracle_public_key: None,
+        oracle_timeout: 0,
+        oracle_fallback: OracleFallback::Refund,
+        commitment: None,
+        reveal_window: 0,
+        reveal_bond: 0,
+        protocol_fee_bp: 0,
+        treasury_address: None,
+    }
+}
+
+#[test]
+fn test_init_twice_fails() {
+    let env = Env::default();
+    let (client, admin, treasury) = setup_factory(&env);
+
+    let result = client.try_init(
+        &admin,
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &0u32,
+        &treasury,
+    );
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+}
+
+#[test]
+fn test_set_protocol_fee_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    client.set_protocol_fee(&250u32);
+
+    let event = env.events().all().last().unwrap();
+    let fee_updated: FeeUpdated = event.2.into_val(&env);
+    assert_eq!(fee_updated.old_fee_bp, 500);
+    assert_eq!(fee_updated.new_fee_bp, 250);
+    assert_eq!(fee_updated.updated_by, admin);
+    assert_eq!(client.get_protocol_fee(), 250);
+}
+
+#[test]
+fn test_protocol_fee_bound() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, treasury) = setup_factory(&env);
+
+    assert_eq!(
+        client.try_set_protocol_fee(&10_001u32),
+        Err(Ok(Error::InvalidParameters))
+    );
+    assert_eq!(
+        client.try_set_config(&10_001u32, &treasury),
+        Err(Ok(Error::InvalidParameters))
+    );
+
+    client.set_protocol_fee(&10_000u32);
+    assert_eq!(client.get_protocol_fee(), 10_000);
+}
+
+#[test]
+#[should_panic]
+fn test_set_protocol_fee_requires_admin() {
+    let env = Env::default();
+    let (client, _, _) = setup_factory(&env);
+
+    client.set_protocol_fee(&100u32);
+}
+
+#[test]
+fn test_set_treasury_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, old_treasury) = setup_factory(&env);
+
+    let new_treasury = Address::generate(&env);
+    client.set_treasury(&new_treasury);
+
+    let event = env.events().all().last().unwrap();
+    let treasury_updated: TreasuryUpdated = event.2.into_val(&env);
+    assert_eq!(treasury_updated.old_treasury, Some(old_treasury));
+    assert_eq!(treasury_updated.new_treasury, new_treasury);
+    assert_eq!(client.get_treasury(), Some(new_treasury));
+}
+
+#[test]
+fn test_set_config_emits_fee_and_treasury_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _) = setup_factory(&env);
+
+    let new_treasury = Address::generate(&env);
+    client.set_config(&100u32, &new_treasury);
+
+    assert_eq!(env.events().all().len(), 2);
+    assert_eq!(client.get_protocol_fee(), 100);
+    assert_eq!(client.get_treasury(), Some(new_treasury));
+}
+
+#[test]
+fn test_set_oracle_address_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    let first = Address::generate(&env);
+    let public_key = BytesN::from_array(&env, &[1u8; 32]);
+    client.set_oracle_address(&first, &public_key);
+    let event = env.events().all().last().unwrap();
+    let oracle_updated: OracleAddressUpdated = event.2.into_val(&env);
+    assert_eq!(oracle_updated.old_oracle, None);
+    assert_eq!(oracle_updated.new_oracle, first.clone());
+    assert_eq!(oracle_updated.updated_by, admin);
+
+    let second = Address::generate(&env);
+    client.set_oracle_address(&second, &public_key);
+    let event = env.events().all().last().unwrap();
+    let oracle_updated: OracleAddressUpdated = event.2.into_val(&env);
+    assert_eq!(oracle_updated.old_oracle, Some(first));
+    assert_eq!(client.get_oracle_address(), Some(second));
+    assert_eq!(client.get_oracle_public_key(), Some(public_key));
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    client.pause();
+    let event = env.events().all().last().unwrap();
+    let paused: ContractPaused = event.2.into_val(&env);
+    assert_eq!(paused.paused_by, admin);
+    assert!(client.is_paused());
+    assert_eq!(client.try_pause(), Err(Ok(Error::InvalidStateTransition)));
+
+    client.unpause();
+    let event = env.events().all().last().unwrap();
+    let unpaused: ContractUnpaused = event.2.into_val(&env);
+    assert_eq!(unpaused.unpaused_by, admin);
+    assert!(!client.is_paused());
+}
+
+#[test]
+fn test_withdraw_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, treasury) = setup_factory(&env);
+
+    let token_admin = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_id);
+    token_admin_client.mint(&creator, &100i128);
+    token_admin_client.mint(&buyer, &10i128);
+
+    // Wired up the way create_raffle does it: instance fees accrue at the factory
+    let raffle = instance::ContractClient::new(&env, &env.register(instance::Contract, ()));
+    let config = RaffleConfig {
+        max_tickets: 1,
+        protocol_fee_bp: 500,
+        treasury_address: Some(client.address.clone()),
+        ..raffle_config(&env, &token_id)
+    };
+    raffle.init(&client.address, &creator, &config);
+    raffle.deposit_prize();
+    raffle.buy_ticket(&buyer);
+    raffle.finalize_raffle(&creator);
+    assert_eq!(raffle.claim_prize(&buyer), 95);
+
+    let token_client = token::Client::new(&env, &token_id);
+    assert_eq!(token_client.balance(&client.address), 5i128);
+
+    client.withdraw_fees(&token_id, &5i128);
+
+    let event = env.events().all().last().unwrap();
+    let withdrawn: FeesWithdrawn = event.2.into_val(&env);
+    assert_eq!(withdrawn.recipient, treasury.clone());
+    assert_eq!(withdrawn.amount, 5i128);
+    assert_eq!(withdrawn.token, token_id.clone());
+
+    assert_eq!(token_client.balance(&treasury), 5i128);
+    assert_eq!(token_client.balance(&client.address), 0i128);
+    assert_eq!(
+        client.try_withdraw_fees(&token_id, &0i128),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+#[test]
+fn test_admin_transfer_two_step() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&new_admin);
+    let event = env.events().all().last().unwrap();
+    let proposed: AdminTransferProposed = event.2.into_val(&env);
+    assert_eq!(proposed.current_admin, admin.clone());
+    assert_eq!(proposed.proposed_admin, new_admin.clone());
+
+    // Proposal alone does not move control
+    assert_eq!(client.get_admin(), admin.clone());
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+    client.accept_admin();
+    let event = env.events().all().last().unwrap();
+    let accepted: AdminTransferAccepted = event.2.into_val(&env);
+    assert_eq!(accepted.old_admin, admin);
+    assert_eq!(accepted.new_admin, new_admin.clone());
+
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_accept_admin_requires_proposed_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&new_admin);
+    client.accept_admin();
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, new_admin);
+    assert_ne!(auths[0].0, admin);
+}
+
+#[test]
+fn test_cancel_admin_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup_factory(&env);
+
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+    assert_eq!(
+        client.try_cancel_admin_proposal(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+
+    client.propose_admin(&Address::generate(&env));
+    client.cancel_admin_proposal();
+
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Error::InvalidStateTransition))
+    );
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_create_raffle_blocked_while_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _) = setup_factory(&env);
+
+    let token_admin = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let config = raffle_config(&env, &token_id);
+
+    client.pause();
+    assert_eq!(
+        client.try_create_raffle(&Address::generate(&env), &config),
+        Err(Ok(Error::ContractPaused))
+    );
+}
+
+#[test]
+fn test_create_raffle_rejects_creator_fee_settings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, treasury) = setup_factory(&env);
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let creator = Address::generate(&env);
+
+    let config = RaffleConfig {
+        protocol_fee_bp: 100,
+        ..raffle_config(&env, &token_id)
+    };
+    assert_eq!(
+        client.try_create_raffle(&creator, &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+
+    let config = RaffleConfig {
+        treasury_address: Some(treasury),
+        ..raffle_config(&env, &token_id)
+    };
+    assert_eq!(
+        client.try_create_raffle(&creator, &config),
+        Err(Ok(Error::InvalidParameters))
+    );
+}
+
+// Built by `make build`, which compiles the instance with `--features instance`.
+// Gated so a plain `cargo test` never runs against a missing or stale wasm.
+#[cfg(feature = "deploy-test")]
+const INSTANCE_WASM: &[u8] =
+    include_bytes!("../../../target/instance/wasm32v1-none/release/hello_world.wasm");
+
+#[cfg(feature = "deploy-test")]
+#[test]
+fn test_create_raffle_deploys_and_initializes_instance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let wasm_hash = env.deployer().upload_contract_wasm(INSTANCE_WASM);
+    let factory_id = env.register(RaffleFactory, ());
+    let client = RaffleFactoryClient::new(&env, &factory_id);
+    client.init(&admin, &wasm_hash, &500u32, &treasury);
+
+    let token_admin = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let creator = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token_id).mint(&creator, &100i128);
+    let config = raffle_config(&env, &token_id);
+
+    let raffle_id = client.create_raffle(&creator, &config);
+    assert_eq!(
+        client.get_raffles(),
+        Vec::from_array(&env, [raffle_id.clone()])
+    );
+
+    let raffle_client = instance::ContractClient::new(&env, &raffle_id);
+    let raffle = raffle_client.get_raffle();
+    assert_eq!(raffle.creator, creator);
+    assert_eq!(raffle.description, config.description);
+    assert_eq!(raffle.max_tickets, 5);
+    assert_eq!(raffle.prize_amount, 100);
+    assert_eq!(raffle.status, instance::RaffleStatus::Proposed);
+    assert_eq!(raffle.protocol_fee_bp, 500);
+    assert_eq!(raffle.treasury_address, Some(factory_id.clone()));
+
+    // The deployed instance is live and reports back to the factory's pause switch.
+    raffle_client.deposit_prize();
+    assert_eq!(
+        raffle_client.get_raffle().status,
+        instance::RaffleStatus::Active
+    );
+    client.pause();
+    assert_eq!(
+        raffle_client.try_buy_ticket(&Address::generate(&env)),
+        Err(Ok(instance::Error::ContractPaused))
+    );
+}